@@ -6,7 +6,20 @@ use super::*;
 
 pub struct Context {
   pub ecs: Arc<RwLock<ecs::Context>>,
-  pub gfx: Arc<gfx::Context>,
+  /// Runs scripted tasks against the `WORLD` game clock once per frame of the
+  /// main loop.
+  pub executor: time::Executor,
+  /// The graphics context, or `None` if the application is headless.
+  pub gfx: Option<Arc<gfx::Context>>,
   pub logger: log::Logger,
-  pub window: window::Handle,
+  /// The application window, or `None` if the application is headless.
+  pub window: Option<window::Handle>,
+}
+
+impl Context {
+  /// Returns `true` if the application was started without a window or
+  /// graphics context.
+  pub fn is_headless(&self) -> bool {
+    self.window.is_none()
+  }
 }
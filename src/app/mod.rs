@@ -3,9 +3,11 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 mod context;
+mod run;
+mod schedule;
 mod settings;
 
-pub use self::{context::*, settings::*};
+pub use self::{context::*, run::*, schedule::*, settings::*};
 
 use super::*;
 
@@ -15,13 +17,23 @@ pub fn start(
   settings: Settings,
 ) -> Result<Context, StartError> {
   let ecs = Arc::new(RwLock::new(ecs::Context::new()));
+  let executor = time::Executor::new();
+
+  // In headless mode, skip the window and graphics device entirely so that the
+  // application can run on machines without a display or GPU.
+  if settings.headless {
+    log::info!(&logger, "starting in headless mode");
+
+    return Ok(Context { ecs, executor, gfx: None, logger, window: None });
+  }
+
   let gfx = Arc::new(gfx::Context::new(logger.clone())?);
 
   let window = window::open(thread_scope, settings.window)?;
 
   gfx::renderer::start(&thread_scope, gfx.clone(), &window, logger.clone())?;
 
-  Ok(Context { ecs, gfx, logger, window })
+  Ok(Context { ecs, executor, gfx: Some(gfx), logger, window: Some(window) })
 }
 
 /// An error that occurred while creating a new application context.
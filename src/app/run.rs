@@ -0,0 +1,145 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+
+/// A resource which stops the main loop at the end of the current frame when
+/// set to `true`.
+///
+/// Headless applications have no window to close, so they must set this
+/// resource to exit the main loop.
+#[derive(Debug, Default)]
+pub struct ExitRequested(pub bool);
+
+/// Runs the main loop until the window is closed or `ExitRequested` is set.
+///
+/// Each frame, the loop writes any new window events to the
+/// `ecs::EventChannel<window::Event>` resource, ticks a `time::Clock`
+/// synchronized to the fixed interval of the schedule, and advances the
+/// `time::GameClocks` resource, which also publishes the clock's interval
+/// progress for interpolation in the `Render` phase. It then runs each `Phase`
+/// in order, polling the tasks of the context's `executor` between the `Update`
+/// and `AfterUpdate` phases. The `Render` phase is skipped if the application
+/// is headless.
+///
+/// The duration of each frame and each phase is recorded in the
+/// `time::FrameStats` resource.
+pub fn run(context: &mut Context, schedule: &mut Schedule) {
+  let mut clock = time::Clock::new();
+
+  clock.set_interval(schedule.fixed_interval());
+  clock.set_intervals_synchronized(true);
+  clock.set_max_intervals_per_tick(schedule.max_fixed_updates_per_frame());
+
+  {
+    let mut ecs = context.ecs.write();
+
+    ecs.put_resource(ExitRequested(false));
+    ecs.put_resource(ecs::EventChannel::<window::Event>::new());
+
+    // Keep any game clocks or frame stats the application set up before the
+    // loop started.
+    if !ecs.has_resource::<time::GameClocks>() {
+      ecs.put_resource(time::GameClocks::new());
+    }
+
+    if !ecs.has_resource::<time::FrameStats>() {
+      ecs.put_resource(time::FrameStats::new());
+    }
+  }
+
+  let mut window_events = Vec::new();
+
+  log::info!(&context.logger, "main loop started";
+    "fixed_interval" => schedule.fixed_interval(),
+  );
+
+  loop {
+    if let Some(window) = &mut context.window {
+      while let Some(event) = window.next_event() {
+        window_events.push(event);
+      }
+    }
+
+    // Stop as soon as the user asks to close the window.
+    if window_events.contains(&window::Event::CloseRequested) {
+      break;
+    }
+
+    clock.tick();
+
+    let mut ecs = context.ecs.write();
+
+    ecs.resource_mut::<ecs::EventChannel<window::Event>>().iter_write(window_events.drain(..));
+    ecs.resource_mut::<time::GameClocks>().advance(&clock);
+
+    // Run each phase, timing how long each one takes.
+    let mut phase_times = Vec::with_capacity(Phase::ALL.len() + 1);
+
+    run_timed_phase(schedule, Phase::BeforeUpdate, &mut ecs, 1, &mut phase_times);
+
+    let fixed_updates = clock.elapsed_intervals_delta();
+
+    run_timed_phase(schedule, Phase::FixedUpdate, &mut ecs, fixed_updates, &mut phase_times);
+    run_timed_phase(schedule, Phase::Update, &mut ecs, 1, &mut phase_times);
+
+    // Release the ECS context while running tasks so that they can lock it.
+    let game_time = ecs.resource::<time::GameClocks>().world().elapsed();
+
+    drop(ecs);
+
+    let started_at = time::now();
+
+    context.executor.run_frame(game_time);
+
+    phase_times.push(("tasks", time::now() - started_at));
+
+    let mut ecs = context.ecs.write();
+
+    run_timed_phase(schedule, Phase::AfterUpdate, &mut ecs, 1, &mut phase_times);
+
+    if !context.is_headless() {
+      run_timed_phase(schedule, Phase::Render, &mut ecs, 1, &mut phase_times);
+    }
+
+    // Record frame statistics and periodically log a summary.
+    {
+      let mut stats = ecs.resource_mut::<time::FrameStats>();
+
+      stats.record_frame(clock.elapsed_delta());
+
+      for (name, duration) in phase_times {
+        stats.record_phase(name, duration);
+      }
+
+      stats.log_summary_if_due(&context.logger);
+    }
+
+    if ecs.resource::<ExitRequested>().0 {
+      break;
+    }
+  }
+
+  log::info!(&context.logger, "main loop stopped");
+}
+
+/// Runs a phase of the schedule `count` times and records how long it took in
+/// total.
+fn run_timed_phase(
+  schedule: &mut Schedule,
+  phase: Phase,
+  ecs: &mut ecs::Context,
+  count: u64,
+  times: &mut Vec<(&'static str, time::Duration)>,
+) {
+  crate::profile_scope!(phase.name());
+
+  let started_at = time::now();
+
+  for _ in 0..count {
+    schedule.run_phase(phase, ecs);
+  }
+
+  times.push((phase.name(), time::now() - started_at));
+}
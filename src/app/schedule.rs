@@ -0,0 +1,127 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+
+/// One of the named phases of a frame of the main loop.
+///
+/// Phases run in the order they are declared here. The `FixedUpdate` phase
+/// runs once for each fixed interval elapsed since the previous frame, which
+/// may be zero or more times per frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+  BeforeUpdate,
+  FixedUpdate,
+  Update,
+  AfterUpdate,
+  Render,
+}
+
+impl Phase {
+  /// All phases in the order in which they run.
+  pub const ALL: [Phase; 5] =
+    [Phase::BeforeUpdate, Phase::FixedUpdate, Phase::Update, Phase::AfterUpdate, Phase::Render];
+
+  /// Returns the name of the phase in kebab case, such as `"fixed-update"`.
+  pub fn name(self) -> &'static str {
+    match self {
+      Phase::BeforeUpdate => "before-update",
+      Phase::FixedUpdate => "fixed-update",
+      Phase::Update => "update",
+      Phase::AfterUpdate => "after-update",
+      Phase::Render => "render",
+    }
+  }
+
+  /// Returns the index of the phase in `Phase::ALL`.
+  fn index(self) -> usize {
+    self as usize
+  }
+}
+
+impl fmt::Display for Phase {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+/// A function that runs during a phase of the main loop.
+type Handler = Box<dyn FnMut(&mut ecs::Context)>;
+
+/// Describes what runs during each phase of the main loop and how often the
+/// `FixedUpdate` phase runs.
+pub struct Schedule {
+  fixed_interval: time::Duration,
+  max_fixed_updates_per_frame: Option<u64>,
+  handlers: Vec<Vec<Handler>>,
+}
+
+impl Schedule {
+  /// Creates a new, empty schedule with a fixed interval of 1/60th of a second
+  /// and at most five fixed updates per frame.
+  pub fn new() -> Self {
+    Self {
+      fixed_interval: time::hz(60.0),
+      max_fixed_updates_per_frame: Some(5),
+      handlers: Phase::ALL.iter().map(|_| Vec::new()).collect(),
+    }
+  }
+
+  /// Returns the duration between runs of the `FixedUpdate` phase.
+  pub fn fixed_interval(&self) -> time::Duration {
+    self.fixed_interval
+  }
+
+  /// Sets the duration between runs of the `FixedUpdate` phase.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if the interval is zero.
+  pub fn set_fixed_interval(&mut self, value: time::Duration) {
+    assert!(value > time::Duration::ZERO, "fixed interval must be greater than zero");
+
+    self.fixed_interval = value;
+  }
+
+  /// Returns the maximum number of times the `FixedUpdate` phase runs per
+  /// frame, or `None` if there is no maximum.
+  pub fn max_fixed_updates_per_frame(&self) -> Option<u64> {
+    self.max_fixed_updates_per_frame
+  }
+
+  /// Sets the maximum number of times the `FixedUpdate` phase runs per frame.
+  ///
+  /// If more fixed intervals than the maximum elapse during a frame, such as
+  /// after a long stall, the extra intervals are skipped instead of making the
+  /// next frame take even longer.
+  pub fn set_max_fixed_updates_per_frame(&mut self, value: Option<u64>) {
+    self.max_fixed_updates_per_frame = value;
+  }
+
+  /// Adds a function to run during the given phase.
+  ///
+  /// Functions added to the same phase run in the order in which they were
+  /// added.
+  pub fn add(&mut self, phase: Phase, handler: impl FnMut(&mut ecs::Context) + 'static) {
+    self.handlers[phase.index()].push(Box::new(handler));
+  }
+
+  /// Adds a dispatcher whose systems run during the given phase.
+  pub fn add_dispatcher(&mut self, phase: Phase, mut dispatcher: ecs::Dispatcher) {
+    self.add(phase, move |ecs| dispatcher.run(ecs));
+  }
+
+  /// Runs all functions added to the given phase.
+  pub fn run_phase(&mut self, phase: Phase, ecs: &mut ecs::Context) {
+    for handler in &mut self.handlers[phase.index()] {
+      handler(ecs);
+    }
+  }
+}
+
+impl Default for Schedule {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -10,6 +10,16 @@ pub type TomlError = toml::de::Error;
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Settings {
+  /// Whether to start without a window or graphics context.
+  ///
+  /// Headless applications still have an ECS context and can run the main
+  /// loop, which is useful for servers, tools, and automated tests.
+  #[serde(default)]
+  pub headless: bool,
+  /// Logging options, which should be passed to `log::init_with` before
+  /// starting the application.
+  #[serde(default)]
+  pub log: log::Settings,
   #[serde(default)]
   pub window: window::Settings,
 }
@@ -0,0 +1,222 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use std::cell::Cell;
+
+thread_local! {
+  static SYSTEM_ORDER: Cell<usize> = Cell::new(0);
+}
+
+/// Provides deferred creation, modification, and deletion of entities.
+///
+/// Unlike `ComponentsMut`, this struct only needs shared access to resources,
+/// so any number of systems can use it at the same time. Commands are queued
+/// and only applied when `Context::commit_entities()` is called.
+#[derive(SystemData)]
+pub struct Commands<'a> {
+  entities: specs::Entities<'a>,
+  queue: specs::Read<'a, CommandQueue>,
+}
+
+impl<'a> Commands<'a> {
+  /// Creates a new entity immediately and returns it.
+  ///
+  /// The entity can be used in other commands right away, but it will not
+  /// have any components until the commands are applied.
+  pub fn spawn(&self) -> Entity {
+    self.entities.create()
+  }
+
+  /// Queues a command to set the component of type `C` for the given entity.
+  ///
+  /// If the entity is not alive when the command is applied, the component is
+  /// dropped.
+  pub fn insert<C: Component + Send>(&self, entity: Entity, value: C) {
+    self.queue.push(entity, move |world| {
+      let _ = world.write_storage::<C>().insert(entity, value);
+    });
+  }
+
+  /// Queues a command to remove the component of type `C` from the given
+  /// entity.
+  pub fn remove<C: Component>(&self, entity: Entity) {
+    self.queue.push(entity, move |world| {
+      world.write_storage::<C>().remove(entity);
+    });
+  }
+
+  /// Queues a command to delete the given entity.
+  pub fn delete(&self, entity: Entity) {
+    self.queue.push(entity, move |world| {
+      let _ = world.entities().delete(entity);
+    });
+  }
+}
+
+/// A resource containing the queued commands of `Commands`.
+#[derive(Default)]
+pub struct CommandQueue {
+  commands: Mutex<Vec<Command>>,
+}
+
+impl CommandQueue {
+  /// Adds a command to the queue.
+  fn push(&self, entity: Entity, apply: impl FnOnce(&mut specs::World) + Send + 'static) {
+    let system = SYSTEM_ORDER.with(Cell::get);
+
+    self.commands.lock().push(Command { entity, system, apply: Box::new(apply) });
+  }
+
+  /// Removes all commands from the queue and returns them in the order in
+  /// which they should be applied.
+  ///
+  /// Commands are sorted by entity and then by the order in which their
+  /// systems were added to the dispatcher, so systems running in parallel
+  /// always apply their commands in the same order. Commands queued outside of
+  /// a system come first. Commands from the same system are kept in the order
+  /// in which they were queued, so removing and then inserting a component
+  /// leaves the entity with the new component.
+  pub(super) fn take_sorted(&self) -> Vec<Command> {
+    let mut commands = mem::replace(&mut *self.commands.lock(), Vec::new());
+
+    // The sort is stable, which preserves queue order for each system.
+    commands.sort_by_key(|command| (command.entity.id(), command.system));
+    commands
+  }
+}
+
+/// Sets the order of the system running on the current thread, which is used
+/// to sort the commands it queues, until the returned guard is dropped.
+///
+/// Systems are numbered from one in the order they were added to a dispatcher.
+/// Commands queued from other threads, or when no system is running, use zero.
+pub(super) fn set_system_order(order: usize) -> SystemOrderGuard {
+  SystemOrderGuard { previous: SYSTEM_ORDER.with(|current| current.replace(order)) }
+}
+
+/// Restores the previous system order of the current thread when dropped.
+pub(super) struct SystemOrderGuard {
+  previous: usize,
+}
+
+impl Drop for SystemOrderGuard {
+  fn drop(&mut self) {
+    SYSTEM_ORDER.with(|current| current.set(self.previous));
+  }
+}
+
+/// A queued command.
+pub(super) struct Command {
+  entity: Entity,
+  system: usize,
+  apply: Box<dyn FnOnce(&mut specs::World) + Send>,
+}
+
+impl Command {
+  /// Applies the command to the given world.
+  pub(super) fn apply(self, world: &mut specs::World) {
+    (self.apply)(world);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::thread;
+  use std::time::Duration;
+
+  #[derive(Debug, Clone, Copy, PartialEq)]
+  struct Tag(&'static str);
+
+  impl Component for Tag {
+    type Storage = storage::DenseVecStorage<Self>;
+  }
+
+  /// Queues a `Tag` for the given entity, optionally after a delay.
+  struct InsertTag {
+    entity: Entity,
+    tag: Tag,
+    delay: Duration,
+  }
+
+  impl<'a> System<'a> for InsertTag {
+    type SystemData = Commands<'a>;
+
+    fn run(&mut self, commands: Self::SystemData) {
+      thread::sleep(self.delay);
+      commands.insert(self.entity, self.tag);
+    }
+  }
+
+  fn tag(ecs: &Context, entity: Entity) -> Option<Tag> {
+    ecs.components::<Tag>().get(entity).cloned()
+  }
+
+  #[test]
+  fn applies_commands_for_an_entity_in_queue_order() {
+    let mut ecs = Context::new();
+
+    ecs.register_component::<Tag>();
+
+    let entity = ecs.entities().create();
+
+    ecs.components_mut::<Tag>().insert(entity, Tag("old"));
+    ecs.commit_entities();
+
+    ecs.commands().remove::<Tag>(entity);
+    ecs.commands().insert(entity, Tag("new"));
+    ecs.commit_entities();
+
+    assert_eq!(tag(&ecs, entity), Some(Tag("new")));
+
+    ecs.commands().insert(entity, Tag("old"));
+    ecs.commands().remove::<Tag>(entity);
+    ecs.commit_entities();
+
+    assert_eq!(tag(&ecs, entity), None);
+  }
+
+  #[test]
+  fn applies_commands_in_system_order_regardless_of_queue_order() {
+    let mut ecs = Context::new();
+
+    ecs.register_component::<Tag>();
+
+    let entity = ecs.entities().create();
+
+    for &(order, value) in &[(2, "second"), (1, "first")] {
+      let _guard = set_system_order(order);
+
+      ecs.commands().insert(entity, Tag(value));
+    }
+
+    ecs.commit_entities();
+
+    assert_eq!(tag(&ecs, entity), Some(Tag("second")));
+  }
+
+  #[test]
+  fn applies_commands_from_parallel_systems_in_dispatcher_order() {
+    let mut ecs = Context::new();
+
+    ecs.register_component::<Tag>();
+
+    let entity = ecs.entities().create();
+
+    // The first system waits so that the second usually queues its command
+    // first.
+    let mut dispatcher = Dispatcher::builder()
+      .with(InsertTag { entity, tag: Tag("first"), delay: Duration::from_millis(5) }, "first", &[])
+      .with(InsertTag { entity, tag: Tag("second"), delay: Duration::default() }, "second", &[])
+      .build(&mut ecs);
+
+    for _ in 0..10 {
+      dispatcher.run(&mut ecs);
+      ecs.commit_entities();
+
+      assert_eq!(tag(&ecs, entity), Some(Tag("second")));
+    }
+  }
+}
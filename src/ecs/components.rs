@@ -4,8 +4,9 @@
 
 pub use specs::{Component, Join, ParJoin};
 
-use super::storage::UnprotectedStorage;
+use super::storage::{ComponentEvent, Tracked, UnprotectedStorage};
 use super::*;
+use std::marker::PhantomData;
 
 /// Provides read-only access to the components of type `C` in an instance.
 #[derive(SystemData)]
@@ -17,6 +18,18 @@ impl<'a, C: Component> Components<'a, C> {
   pub fn get(&self, entity: Entity) -> Option<&C> {
     self.0.get(entity)
   }
+
+  /// Returns the changes to components of type `C` since the given reader last
+  /// read changes.
+  ///
+  /// Change tracking is opt-in and requires a tracked storage type such as
+  /// `storage::FlaggedStorage`.
+  pub fn changes(&self, reader: &mut ChangeReaderId<C>) -> ComponentChanges
+  where
+    C::Storage: Tracked,
+  {
+    ComponentChanges::from_events(self.0.channel().read(&mut reader.inner))
+  }
 }
 
 impl<'a, C: Component> Join for &'a Components<'a, C> {
@@ -67,6 +80,27 @@ impl<'a, C: Component> ComponentsMut<'a, C> {
   pub fn remove(&mut self, entity: Entity) -> Option<C> {
     self.0.remove(entity)
   }
+
+  /// Registers a new reader of changes to components of type `C`.
+  ///
+  /// The first read of the new reader will only return changes made after
+  /// this call. Change tracking is opt-in and requires a tracked storage type
+  /// such as `storage::FlaggedStorage`.
+  pub fn register_change_reader(&mut self) -> ChangeReaderId<C>
+  where
+    C::Storage: Tracked,
+  {
+    ChangeReaderId { inner: self.0.register_reader(), _marker: PhantomData }
+  }
+
+  /// Returns the changes to components of type `C` since the given reader last
+  /// read changes.
+  pub fn changes(&self, reader: &mut ChangeReaderId<C>) -> ComponentChanges
+  where
+    C::Storage: Tracked,
+  {
+    ComponentChanges::from_events(self.0.channel().read(&mut reader.inner))
+  }
 }
 
 impl<'a, 'b, C: Component> Join for &'a mut ComponentsMut<'b, C> {
@@ -84,3 +118,54 @@ impl<'a, 'b, C: Component> Join for &'a mut ComponentsMut<'b, C> {
     (*value).get_mut(index)
   }
 }
+
+/// The position of a reader of changes to components of type `C`.
+pub struct ChangeReaderId<C> {
+  inner: specs::ReaderId<ComponentEvent>,
+  _marker: PhantomData<fn() -> C>,
+}
+
+/// The sets of entities with changed components, as returned by the
+/// `changes()` method of `Components` or `ComponentsMut`.
+///
+/// Each set can be used in a `Join` to iterate only the changed entities, for
+/// example `(&entities, &components, &changes.modified).join()`. A component
+/// that changed more than once, such as one that was inserted and then
+/// modified, is included in each matching set.
+#[derive(Debug, Default)]
+pub struct ComponentChanges {
+  /// Entities whose component was inserted.
+  pub inserted: BitSet,
+  /// Entities whose component was modified through mutable access.
+  pub modified: BitSet,
+  /// Entities whose component was removed.
+  pub removed: BitSet,
+}
+
+impl ComponentChanges {
+  /// Returns the set of entities whose component was either inserted or
+  /// modified.
+  pub fn inserted_or_modified(&self) -> BitSetOr<&BitSet, &BitSet> {
+    BitSetOr(&self.inserted, &self.modified)
+  }
+
+  /// Returns `true` if no components changed.
+  pub fn is_empty(&self) -> bool {
+    self.inserted.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+  }
+
+  /// Collects the changes from events read from a component event channel.
+  fn from_events<'a>(events: impl Iterator<Item = &'a ComponentEvent>) -> Self {
+    let mut changes = Self::default();
+
+    for event in events {
+      match event {
+        ComponentEvent::Inserted(index) => changes.inserted.add(*index),
+        ComponentEvent::Modified(index) => changes.modified.add(*index),
+        ComponentEvent::Removed(index) => changes.removed.add(*index),
+      };
+    }
+
+    changes
+  }
+}
@@ -5,9 +5,10 @@
 use super::*;
 
 /// A standalone ECS context containing resources, entities, and components.
-#[derive(Default)]
 pub struct Context {
-  world: specs::World,
+  pub(super) world: specs::World,
+  pub(super) registry: ComponentRegistry,
+  pub(super) snapshot_versioning: SnapshotVersioning,
 }
 
 impl Context {
@@ -30,6 +31,11 @@ impl Context {
     self.world.system_data()
   }
 
+  /// Returns `true` if a resource of type `R` exists.
+  pub fn has_resource<R: ResourceLike>(&self) -> bool {
+    self.world.res.has_value::<R>()
+  }
+
   /// Sets the available resource of type `R`.
   ///
   /// If a resource of type `R` already exists, the old value dropped.
@@ -42,12 +48,28 @@ impl Context {
     self.world.system_data()
   }
 
-  /// Finalizes the creation and deletion of any entities created or deleted
-  /// since the previous call to this function.
+  /// Returns a `Commands` struct for queueing changes to entities and
+  /// components that are applied by `commit_entities()`.
+  pub fn commands(&self) -> Commands {
+    self.world.system_data()
+  }
+
+  /// Applies all queued `Commands` and then finalizes the creation and
+  /// deletion of any entities created or deleted since the previous call to
+  /// this function.
   ///
-  /// A deleted entity is still considered alive until this function is called.
+  /// Deleting an entity also deletes all of its descendants in the `Parent`
+  /// hierarchy. A deleted entity is still considered alive until this function
+  /// is called.
   pub fn commit_entities(&mut self) {
+    let commands = self.world.read_resource::<CommandQueue>().take_sorted();
+
+    for command in commands {
+      command.apply(&mut self.world);
+    }
+
     self.world.maintain();
+    self.delete_orphans();
   }
 
   /// Registers a possible component type.
@@ -69,4 +91,30 @@ impl Context {
   pub fn components_mut<C: Component>(&self) -> ComponentsMut<C> {
     self.world.system_data()
   }
+
+  /// Runs the given system once on the current thread.
+  ///
+  /// Any components or resources the system requires must already exist in
+  /// this context.
+  pub fn run_system<S: for<'a> System<'a>>(&mut self, system: &mut S) {
+    system.run_now(&self.world.res);
+  }
+}
+
+impl Default for Context {
+  fn default() -> Self {
+    let mut world = specs::World::new();
+
+    world.add_resource(CommandQueue::default());
+    world.register::<Parent>();
+    world.register::<Children>();
+    world.register::<LocalTransform>();
+    world.register::<GlobalTransform>();
+
+    Self {
+      world,
+      registry: ComponentRegistry::default(),
+      snapshot_versioning: SnapshotVersioning::default(),
+    }
+  }
 }
@@ -0,0 +1,159 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+pub use specs::{RunNow, System, SystemData};
+
+use super::*;
+
+/// Runs a set of systems on an ECS context, in parallel where possible.
+///
+/// Systems declare the resources and components they need through their
+/// `SystemData`, such as `Resource<R>` or `ComponentsMut<C>`. Systems that do
+/// not write to anything another system reads or writes may run at the same
+/// time on different threads.
+pub struct Dispatcher {
+  inner: specs::Dispatcher<'static, 'static>,
+}
+
+impl Dispatcher {
+  /// Returns a new `DispatcherBuilder` for adding systems to a dispatcher.
+  pub fn builder() -> DispatcherBuilder {
+    DispatcherBuilder::default()
+  }
+
+  /// Runs all systems in the dispatcher once on the given ECS context.
+  ///
+  /// This function blocks until all systems have finished.
+  pub fn run(&mut self, ecs: &mut Context) {
+    self.inner.dispatch(&ecs.world.res);
+  }
+}
+
+/// Builds a `Dispatcher` from a set of systems.
+#[derive(Default)]
+pub struct DispatcherBuilder {
+  inner: specs::DispatcherBuilder<'static, 'static>,
+  systems: usize,
+}
+
+impl DispatcherBuilder {
+  /// Adds a system with a unique name and the names of any systems that must
+  /// finish before it runs.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if the name is already used or if a dependency has
+  /// not been added.
+  pub fn with<S>(mut self, system: S, name: &str, dependencies: &[&str]) -> Self
+  where
+    S: for<'a> System<'a> + Send + 'static,
+  {
+    self.add(system, name, dependencies);
+    self
+  }
+
+  /// Adds a system with a unique name and the names of any systems that must
+  /// finish before it runs.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if the name is already used or if a dependency has
+  /// not been added.
+  pub fn add<S>(&mut self, system: S, name: &str, dependencies: &[&str])
+  where
+    S: for<'a> System<'a> + Send + 'static,
+  {
+    self.systems += 1;
+    self.inner.add(Ordered { system, order: self.systems }, name, dependencies);
+  }
+
+  /// Adds a system that always runs on the thread that runs the dispatcher,
+  /// after all other systems.
+  ///
+  /// Thread-local systems run in the order in which they were added.
+  pub fn with_thread_local<S>(mut self, system: S) -> Self
+  where
+    S: for<'a> RunNow<'a> + 'static,
+  {
+    self.systems += 1;
+    self.inner.add_thread_local(OrderedThreadLocal { system, order: self.systems });
+    self
+  }
+
+  /// Adds a barrier so that all systems added after this call will not run
+  /// until all systems added before it have finished.
+  pub fn with_barrier(mut self) -> Self {
+    self.inner.add_barrier();
+    self
+  }
+
+  /// Builds the dispatcher, setting up any components and default resources
+  /// its systems require in the given ECS context.
+  pub fn build(self, ecs: &mut Context) -> Dispatcher {
+    let mut inner = self.inner.build();
+
+    inner.setup(&mut ecs.world.res);
+
+    Dispatcher { inner }
+  }
+}
+
+/// Wraps a system so that its `Commands` are applied in the order it was added
+/// to the dispatcher.
+struct Ordered<S> {
+  system: S,
+  order: usize,
+}
+
+impl<'a, S: System<'a>> System<'a> for Ordered<S> {
+  type SystemData = S::SystemData;
+
+  fn run(&mut self, data: Self::SystemData) {
+    let _guard = commands::set_system_order(self.order);
+
+    self.system.run(data);
+  }
+
+  fn running_time(&self) -> shred::RunningTime {
+    self.system.running_time()
+  }
+
+  fn accessor<'b>(&'b self) -> shred::AccessorCow<'a, 'b, Self> {
+    match self.system.accessor() {
+      shred::AccessorCow::Ref(accessor) => shred::AccessorCow::Ref(accessor),
+      shred::AccessorCow::Owned(accessor) => shred::AccessorCow::Owned(accessor),
+    }
+  }
+
+  fn setup(&mut self, res: &mut shred::Resources) {
+    self.system.setup(res);
+  }
+
+  fn dispose(self, res: &mut shred::Resources) {
+    self.system.dispose(res);
+  }
+}
+
+/// Wraps a thread-local system so that its `Commands` are applied in the order
+/// it was added to the dispatcher.
+struct OrderedThreadLocal<S> {
+  system: S,
+  order: usize,
+}
+
+impl<'a, S: RunNow<'a>> RunNow<'a> for OrderedThreadLocal<S> {
+  fn run_now(&mut self, res: &'a shred::Resources) {
+    let _guard = commands::set_system_order(self.order);
+
+    self.system.run_now(res);
+  }
+
+  fn setup(&mut self, res: &mut shred::Resources) {
+    self.system.setup(res);
+  }
+
+  fn dispose(self: Box<Self>, res: &mut shred::Resources) {
+    Box::new(self.system).dispose(res);
+  }
+}
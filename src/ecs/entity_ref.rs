@@ -0,0 +1,124 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Serde functions for `Entity` fields of persistent components.
+//!
+//! Entities are not meaningful outside of the context that created them, so
+//! references to entities are saved in a `Snapshot` as the snapshot ID of the
+//! entity and remapped to the newly created entities when the snapshot is
+//! restored. Use these functions with `#[serde(with = "...")]`:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Follow {
+//!   #[serde(with = "nova::ecs::entity_ref")]
+//!   target: Entity,
+//!   #[serde(with = "nova::ecs::entity_ref::option")]
+//!   leader: Option<Entity>,
+//! }
+//! ```
+//!
+//! Serializing or deserializing an entity reference outside of
+//! `Context::snapshot()` or `Context::restore()` fails with an error.
+
+use super::*;
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serializer};
+use std::collections::HashMap;
+
+thread_local! {
+  static ENTITY_MAP: RefCell<Option<EntityMap>> = RefCell::new(None);
+}
+
+/// Maps entities to and from snapshot IDs while a snapshot is being saved or
+/// restored on the current thread.
+pub(super) enum EntityMap {
+  Save(HashMap<Entity, u32>),
+  Restore(HashMap<u32, Entity>),
+}
+
+impl EntityMap {
+  /// Sets the entity map of the current thread until the returned guard is
+  /// dropped.
+  pub(super) fn set(self) -> EntityMapGuard {
+    ENTITY_MAP.with(|map| *map.borrow_mut() = Some(self));
+
+    EntityMapGuard
+  }
+}
+
+/// Unsets the entity map of the current thread when dropped.
+pub(super) struct EntityMapGuard;
+
+impl Drop for EntityMapGuard {
+  fn drop(&mut self) {
+    ENTITY_MAP.with(|map| map.borrow_mut().take());
+  }
+}
+
+/// Serializes an entity as its snapshot ID.
+pub fn serialize<S: Serializer>(entity: &Entity, serializer: S) -> Result<S::Ok, S::Error> {
+  let id = ENTITY_MAP.with(|map| match &*map.borrow() {
+    Some(EntityMap::Save(ids)) => Ok(ids.get(entity).cloned()),
+    _ => Err(S::Error::custom("entity references can only be serialized in a snapshot")),
+  })?;
+
+  match id {
+    Some(id) => serializer.serialize_u32(id),
+    None => {
+      Err(S::Error::custom(format!("referenced entity {} is not in the snapshot", entity.id())))
+    }
+  }
+}
+
+/// Deserializes a snapshot ID as the entity restored from it.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Entity, D::Error> {
+  let id = u32::deserialize(deserializer)?;
+
+  let entity = ENTITY_MAP.with(|map| match &*map.borrow() {
+    Some(EntityMap::Restore(entities)) => Ok(entities.get(&id).cloned()),
+    _ => Err(D::Error::custom("entity references can only be deserialized in a snapshot")),
+  })?;
+
+  entity.ok_or_else(|| D::Error::custom(format!("referenced entity {} is not in the snapshot", id)))
+}
+
+/// Serde functions for `Option<Entity>` fields of persistent components.
+pub mod option {
+  use super::*;
+
+  /// Serializes an optional entity as its snapshot ID.
+  pub fn serialize<S: Serializer>(
+    entity: &Option<Entity>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    match entity {
+      Some(entity) => serializer.serialize_some(&Wrapper(*entity)),
+      None => serializer.serialize_none(),
+    }
+  }
+
+  /// Deserializes an optional snapshot ID as the entity restored from it.
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Option<Entity>, D::Error> {
+    Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|wrapper| wrapper.0))
+  }
+
+  /// Wraps an entity to implement serde traits with the parent module.
+  struct Wrapper(Entity);
+
+  impl serde::Serialize for Wrapper {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+      super::serialize(&self.0, serializer)
+    }
+  }
+
+  impl<'de> Deserialize<'de> for Wrapper {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+      super::deserialize(deserializer).map(Wrapper)
+    }
+  }
+}
@@ -0,0 +1,142 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+/// The number of events an `EventChannel` created with `new()` keeps before
+/// discarding the oldest.
+pub const DEFAULT_EVENT_CAPACITY: usize = 1024;
+
+/// A resource containing a bounded history of events of type `T`.
+///
+/// Any number of readers can be registered with `register_reader()`. Each
+/// reader tracks its own position in the channel so that each call to `read()`
+/// returns only the events written since that reader last read.
+///
+/// Events are stored in a ring buffer. Once the buffer is full, writing a new
+/// event discards the oldest one, even if some readers have not yet read it.
+/// Those readers will skip the discarded events on their next read.
+pub struct EventChannel<T> {
+  events: VecDeque<T>,
+  /// The sequence number of the oldest event in `events`.
+  first: u64,
+  capacity: usize,
+}
+
+impl<T> EventChannel<T> {
+  /// Creates a new, empty channel with the default capacity.
+  pub fn new() -> Self {
+    Self::with_capacity(DEFAULT_EVENT_CAPACITY)
+  }
+
+  /// Creates a new, empty channel which keeps at most `capacity` events.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if `capacity` is zero.
+  pub fn with_capacity(capacity: usize) -> Self {
+    assert!(capacity > 0, "event channel capacity must be greater than zero");
+
+    Self { events: VecDeque::with_capacity(capacity), first: 0, capacity }
+  }
+
+  /// Returns the maximum number of events kept in the channel.
+  pub fn capacity(&self) -> usize {
+    self.capacity
+  }
+
+  /// Returns the number of events currently kept in the channel.
+  pub fn len(&self) -> usize {
+    self.events.len()
+  }
+
+  /// Returns `true` if the channel currently has no events.
+  pub fn is_empty(&self) -> bool {
+    self.events.is_empty()
+  }
+
+  /// Registers a new reader of the channel.
+  ///
+  /// The first read of the new reader will only return events written after
+  /// this call.
+  pub fn register_reader(&self) -> EventReaderId<T> {
+    EventReaderId { next: self.end(), missed: 0, _marker: PhantomData }
+  }
+
+  /// Writes a single event to the channel.
+  pub fn single_write(&mut self, event: T) {
+    if self.events.len() == self.capacity {
+      self.events.pop_front();
+      self.first += 1;
+    }
+
+    self.events.push_back(event);
+  }
+
+  /// Writes each event in the given iterator to the channel.
+  pub fn iter_write(&mut self, events: impl IntoIterator<Item = T>) {
+    for event in events {
+      self.single_write(event);
+    }
+  }
+
+  /// Returns an iterator over the events written since the given reader last
+  /// read the channel, then advances the reader past them.
+  pub fn read<'a>(&'a self, reader: &mut EventReaderId<T>) -> impl Iterator<Item = &'a T> + 'a {
+    // Skip ahead if the reader fell behind and some of its events have been
+    // discarded.
+    let start = reader.next.max(self.first);
+
+    reader.missed += start - reader.next;
+    reader.next = self.end();
+
+    self.events.iter().skip((start - self.first) as usize)
+  }
+
+  /// Returns the sequence number the next written event will have.
+  fn end(&self) -> u64 {
+    self.first + self.events.len() as u64
+  }
+}
+
+impl<T> Default for EventChannel<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> fmt::Debug for EventChannel<T> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("EventChannel")
+      .field("len", &self.events.len())
+      .field("capacity", &self.capacity)
+      .finish()
+  }
+}
+
+/// The position of a reader in an `EventChannel<T>`.
+///
+/// Readers should only be used with the channel that registered them.
+pub struct EventReaderId<T> {
+  /// The sequence number of the next event to read.
+  next: u64,
+  missed: u64,
+  _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> EventReaderId<T> {
+  /// Returns the total number of events this reader has skipped because they
+  /// were discarded before it read them.
+  pub fn missed(&self) -> u64 {
+    self.missed
+  }
+}
+
+impl<T> fmt::Debug for EventReaderId<T> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("EventReaderId").field("next", &self.next).field("missed", &self.missed).finish()
+  }
+}
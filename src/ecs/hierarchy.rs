@@ -0,0 +1,312 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::storage::{BTreeStorage, DenseVecStorage};
+use super::*;
+use crate::math::{Point2, Transform2, Vector2};
+use std::collections::HashMap;
+
+/// Component that attaches an entity to a parent entity.
+///
+/// The global transform of an entity with a parent is relative to the global
+/// transform of its parent. When the parent is deleted, the entity is deleted
+/// as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parent(pub Entity);
+
+impl Component for Parent {
+  type Storage = DenseVecStorage<Self>;
+}
+
+/// Component containing the entities whose `Parent` is this entity.
+///
+/// This component is maintained by the `UpdateHierarchy` system and should not
+/// be modified directly.
+#[derive(Debug, Clone, Default)]
+pub struct Children(Vec<Entity>);
+
+impl Children {
+  /// Returns the child entities in ascending order of entity ID.
+  pub fn as_slice(&self) -> &[Entity] {
+    &self.0
+  }
+
+  /// Returns an iterator over the child entities.
+  pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+    self.0.iter().cloned()
+  }
+}
+
+impl Component for Children {
+  type Storage = BTreeStorage<Self>;
+}
+
+/// Component describing the 2D transform of an entity relative to its parent,
+/// or to the world if it has no parent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalTransform {
+  /// Translation of the entity.
+  pub position: Vector2<f32>,
+  /// Counter-clockwise rotation of the entity in radians.
+  pub rotation: f32,
+  /// Scale of the entity on each axis.
+  pub scale: Vector2<f32>,
+}
+
+impl LocalTransform {
+  /// Creates a new local transform with the given position and no rotation or
+  /// scaling.
+  pub fn new(position: Vector2<f32>) -> Self {
+    Self { position, ..Self::default() }
+  }
+
+  /// Returns the transform as a matrix which scales, then rotates, then
+  /// translates.
+  pub fn to_matrix(&self) -> Transform2<f32> {
+    let (sin, cos) = self.rotation.sin_cos();
+
+    Transform2::from_matrix_unchecked(nalgebra::Matrix3::new(
+      self.scale.x * cos,
+      -self.scale.y * sin,
+      self.position.x,
+      self.scale.x * sin,
+      self.scale.y * cos,
+      self.position.y,
+      0.0,
+      0.0,
+      1.0,
+    ))
+  }
+}
+
+impl Default for LocalTransform {
+  fn default() -> Self {
+    Self { position: Vector2::zeros(), rotation: 0.0, scale: Vector2::new(1.0, 1.0) }
+  }
+}
+
+impl Component for LocalTransform {
+  type Storage = DenseVecStorage<Self>;
+}
+
+/// Component containing the 2D transform of an entity relative to the world.
+///
+/// This component is computed from `LocalTransform` by the `UpdateHierarchy`
+/// system and should not be modified directly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlobalTransform(pub Transform2<f32>);
+
+impl GlobalTransform {
+  /// Returns the position of the entity in the world.
+  pub fn position(&self) -> Point2<f32> {
+    self.0 * Point2::origin()
+  }
+}
+
+impl Default for GlobalTransform {
+  fn default() -> Self {
+    GlobalTransform(Transform2::identity())
+  }
+}
+
+impl Component for GlobalTransform {
+  type Storage = DenseVecStorage<Self>;
+}
+
+/// System that updates the `Children` of every entity from their `Parent`
+/// components and then computes a `GlobalTransform` for every entity with a
+/// `LocalTransform`.
+///
+/// Global transforms are computed in parent-first order, so each entity's
+/// global transform is its parent's global transform combined with its own
+/// local transform. An entity without a `LocalTransform` has no
+/// `GlobalTransform` and passes its parent's global transform on to its
+/// children unchanged.
+///
+/// Entities whose `Parent` components form a cycle have no root, so they have
+/// no `GlobalTransform` either.
+#[derive(Debug, Default)]
+pub struct UpdateHierarchy {
+  stack: Vec<(Entity, Transform2<f32>)>,
+}
+
+impl<'a> System<'a> for UpdateHierarchy {
+  type SystemData = (
+    Entities<'a>,
+    Components<'a, Parent>,
+    ComponentsMut<'a, Children>,
+    Components<'a, LocalTransform>,
+    ComponentsMut<'a, GlobalTransform>,
+  );
+
+  fn run(&mut self, (entities, parents, mut children, locals, mut globals): Self::SystemData) {
+    // Rebuild the children of each parent in ascending order of ID.
+    let mut child_lists: HashMap<Entity, Vec<Entity>> = HashMap::new();
+
+    for (entity, parent) in (&entities, &parents).join() {
+      if entities.is_alive(parent.0) {
+        child_lists.entry(parent.0).or_default().push(entity);
+      }
+    }
+
+    let stale: Vec<Entity> = (&entities, &mut children)
+      .join()
+      .filter(|(entity, _)| !child_lists.contains_key(entity))
+      .map(|(entity, _)| entity)
+      .collect();
+
+    for entity in stale {
+      children.remove(entity);
+    }
+
+    for (parent, mut list) in child_lists {
+      list.sort_by_key(|entity| entity.id());
+      children.insert(parent, Children(list));
+    }
+
+    // Walk each tree depth-first from its root, computing global transforms
+    // from the top down.
+    let mut visited = BitSet::new();
+
+    for entity in (&entities).join() {
+      let is_root = match parents.get(entity) {
+        Some(parent) => !entities.is_alive(parent.0),
+        None => true,
+      };
+
+      if is_root {
+        self.stack.push((entity, Transform2::identity()));
+      }
+
+      while let Some((entity, parent_transform)) = self.stack.pop() {
+        // Guard against cycles in the hierarchy.
+        if visited.add(entity.id()) {
+          continue;
+        }
+
+        let transform = match locals.get(entity) {
+          Some(local) => {
+            let transform = parent_transform * local.to_matrix();
+
+            globals.insert(entity, GlobalTransform(transform));
+
+            transform
+          }
+
+          None => {
+            // Don't leave behind the global transform of a removed local one.
+            globals.remove(entity);
+
+            parent_transform
+          }
+        };
+
+        if let Some(list) = children.get(entity) {
+          for child in list.as_slice().iter().rev() {
+            self.stack.push((*child, transform));
+          }
+        }
+      }
+    }
+
+    // Entities in or below a cycle were never reached from a root, so remove
+    // any global transforms they had before the cycle formed.
+    let unreachable: Vec<Entity> =
+      (&entities, &mut globals, !&visited).join().map(|(entity, _, _)| entity).collect();
+
+    for entity in unreachable {
+      globals.remove(entity);
+    }
+  }
+}
+
+impl Context {
+  /// Deletes every entity whose `Parent` is no longer alive, repeating until
+  /// no such entities remain so that entire subtrees are deleted.
+  pub(super) fn delete_orphans(&mut self) {
+    loop {
+      let orphans: Vec<Entity> = {
+        let entities = self.entities();
+        let parents = self.components::<Parent>();
+
+        (&entities, &parents)
+          .join()
+          .filter(|(_, parent)| !entities.is_alive(parent.0))
+          .map(|(entity, _)| entity)
+          .collect()
+      };
+
+      if orphans.is_empty() {
+        return;
+      }
+
+      for entity in orphans {
+        self.entities().delete(entity);
+      }
+
+      self.world.maintain();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn global_position(ecs: &Context, entity: Entity) -> Option<Point2<f32>> {
+    ecs.components::<GlobalTransform>().get(entity).map(GlobalTransform::position)
+  }
+
+  #[test]
+  fn removing_local_transform_removes_global_transform() {
+    let mut ecs = Context::new();
+    let parent = ecs.entities().create();
+    let child = ecs.entities().create();
+
+    ecs
+      .components_mut::<LocalTransform>()
+      .insert(parent, LocalTransform::new(Vector2::new(1.0, 2.0)));
+    ecs
+      .components_mut::<LocalTransform>()
+      .insert(child, LocalTransform::new(Vector2::new(3.0, 4.0)));
+    ecs.components_mut::<Parent>().insert(child, Parent(parent));
+    ecs.commit_entities();
+    ecs.run_system(&mut UpdateHierarchy::default());
+
+    assert_eq!(global_position(&ecs, parent), Some(Point2::new(1.0, 2.0)));
+    assert_eq!(global_position(&ecs, child), Some(Point2::new(4.0, 6.0)));
+
+    ecs.components_mut::<LocalTransform>().remove(parent);
+    ecs.run_system(&mut UpdateHierarchy::default());
+
+    assert_eq!(global_position(&ecs, parent), None);
+    assert_eq!(global_position(&ecs, child), Some(Point2::new(3.0, 4.0)));
+  }
+
+  #[test]
+  fn entities_in_a_parent_cycle_lose_global_transform() {
+    let mut ecs = Context::new();
+    let a = ecs.entities().create();
+    let b = ecs.entities().create();
+    let child = ecs.entities().create();
+
+    for &entity in &[a, b, child] {
+      ecs.components_mut::<LocalTransform>().insert(entity, LocalTransform::default());
+    }
+
+    ecs.components_mut::<Parent>().insert(child, Parent(a));
+    ecs.commit_entities();
+    ecs.run_system(&mut UpdateHierarchy::default());
+
+    assert_eq!(global_position(&ecs, child), Some(Point2::origin()));
+
+    ecs.components_mut::<Parent>().insert(a, Parent(b));
+    ecs.components_mut::<Parent>().insert(b, Parent(a));
+    ecs.run_system(&mut UpdateHierarchy::default());
+
+    assert_eq!(global_position(&ecs, a), None);
+    assert_eq!(global_position(&ecs, b), None);
+    assert_eq!(global_position(&ecs, child), None);
+  }
+}
@@ -0,0 +1,163 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::entity_ref::EntityMap;
+use super::registry::Inspected;
+use super::*;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::io::{self, Write as _};
+
+/// The format of a world dump created with `Context::dump()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpFormat {
+  /// Human-readable text with one line per component.
+  Text,
+  /// A pretty-printed JSON array with one object per entity.
+  Json,
+}
+
+impl Context {
+  /// Registers a component type that is included in world dumps under the
+  /// given name using its `fmt::Debug` representation.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if the name is already registered to a different
+  /// component type.
+  pub fn register_debug_component<C>(&mut self, name: &str)
+  where
+    C: Component + fmt::Debug,
+    C::Storage: Default,
+  {
+    self.register_component::<C>();
+    self.registry.entry::<C>(name).inspect = Some(inspect_debug::<C>);
+  }
+
+  /// Registers a component type that is included in world dumps under the
+  /// given name using its serialized representation.
+  ///
+  /// Entity references serialized with the `entity_ref` functions appear as
+  /// entity IDs.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if the name is already registered to a different
+  /// component type.
+  pub fn register_serialized_debug_component<C>(&mut self, name: &str)
+  where
+    C: Component + Serialize,
+    C::Storage: Default,
+  {
+    self.register_component::<C>();
+    self.registry.entry::<C>(name).inspect = Some(inspect_serialized::<C>);
+  }
+
+  /// Returns a dump of all living entities and their components that were
+  /// registered for debugging.
+  pub fn dump(&self, format: DumpFormat) -> String {
+    let inspectors: Vec<_> = self
+      .registry
+      .iter()
+      .filter_map(|entry| Some((entry.name.as_str(), entry.inspect?)))
+      .collect();
+
+    let entities: Vec<Entity> = (&*self.world.entities()).join().collect();
+
+    // Show entity references as entity IDs.
+    let _guard =
+      EntityMap::Save(entities.iter().map(|entity| (*entity, entity.id())).collect()).set();
+
+    match format {
+      DumpFormat::Text => {
+        let mut text = String::new();
+
+        for entity in entities {
+          let _ = writeln!(text, "entity {}", entity.id());
+
+          for (name, inspect) in &inspectors {
+            match inspect(&self.world, entity) {
+              Some(Inspected::Debug(debug)) => {
+                let _ = writeln!(text, "  {}: {}", name, debug);
+              }
+
+              Some(Inspected::Serialized(Ok(value))) => {
+                let _ = writeln!(text, "  {}: {}", name, value);
+              }
+
+              Some(Inspected::Serialized(Err(err))) => {
+                let _ = writeln!(text, "  {}: <{}>", name, err);
+              }
+
+              None => {}
+            }
+          }
+        }
+
+        text
+      }
+
+      DumpFormat::Json => {
+        let dump: Vec<serde_json::Value> = entities
+          .into_iter()
+          .map(|entity| {
+            let mut components = serde_json::Map::new();
+
+            for (name, inspect) in &inspectors {
+              let value = match inspect(&self.world, entity) {
+                Some(Inspected::Debug(debug)) => serde_json::Value::String(debug),
+                Some(Inspected::Serialized(Ok(value))) => value,
+                Some(Inspected::Serialized(Err(err))) => {
+                  serde_json::json!({ "error": err.to_string() })
+                }
+                None => continue,
+              };
+
+              components.insert(name.to_string(), value);
+            }
+
+            serde_json::json!({ "id": entity.id(), "components": components })
+          })
+          .collect();
+
+        serde_json::to_string_pretty(&dump).expect("could not format world dump as JSON")
+      }
+    }
+  }
+
+  /// Writes a dump of all living entities and their components that were
+  /// registered for debugging to a file in the virtual file system.
+  pub fn write_dump(
+    &self,
+    vfs: &vfs::Context,
+    path: impl AsRef<vfs::Path>,
+    format: DumpFormat,
+  ) -> io::Result<()> {
+    let dump = self.dump(format);
+
+    vfs.create(path)?.write_all(dump.as_bytes())
+  }
+}
+
+/// Describes the component of type `C` for the given entity using its
+/// `fmt::Debug` representation.
+fn inspect_debug<C: Component + fmt::Debug>(
+  world: &specs::World,
+  entity: Entity,
+) -> Option<Inspected> {
+  let storage = world.read_storage::<C>();
+
+  Some(Inspected::Debug(format!("{:?}", storage.get(entity)?)))
+}
+
+/// Describes the component of type `C` for the given entity using its
+/// serialized representation.
+fn inspect_serialized<C: Component + Serialize>(
+  world: &specs::World,
+  entity: Entity,
+) -> Option<Inspected> {
+  let storage = world.read_storage::<C>();
+
+  Some(Inspected::Serialized(serde_json::to_value(storage.get(entity)?)))
+}
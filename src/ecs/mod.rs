@@ -2,13 +2,29 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+pub mod entity_ref;
+
+mod commands;
 mod components;
 mod context;
+mod dispatcher;
 mod entities;
+mod events;
+mod hierarchy;
+mod inspect;
+mod prefabs;
+mod registry;
+mod snapshot;
 
-pub use self::{components::*, context::*, entities::*};
+pub use self::{
+  commands::*, components::*, context::*, dispatcher::*, entities::*, events::*, hierarchy::*,
+  inspect::*, prefabs::*, snapshot::*,
+};
 pub use shred::{ReadExpect as Resource, Resource as ResourceLike, WriteExpect as ResourceMut};
 pub use specs::storage;
 
+use self::registry::ComponentRegistry;
+use self::snapshot::SnapshotVersioning;
+use super::*;
 use hibitset::*;
 use shred_derive::*;
@@ -0,0 +1,139 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use serde::de::DeserializeOwned;
+use std::io;
+
+impl Context {
+  /// Registers a component type that can be loaded from prefab files under
+  /// the given name.
+  ///
+  /// A prefab file is a TOML document with one table per component, named
+  /// with the registered name of the component and containing its fields:
+  ///
+  /// ```toml
+  /// [position]
+  /// x = 12.0
+  /// y = 4.0
+  ///
+  /// [sprite]
+  /// image = "chest.png"
+  /// ```
+  ///
+  /// # Panics
+  ///
+  /// This function panics if the name is already registered to a different
+  /// component type.
+  pub fn register_prefab_component<C>(&mut self, name: &str)
+  where
+    C: Component + DeserializeOwned + Send,
+    C::Storage: Default,
+  {
+    self.register_component::<C>();
+    self.registry.entry::<C>(name).insert_from_toml = Some(insert_from_toml::<C>);
+  }
+
+  /// Creates a new entity from the prefab file at the given path in the
+  /// virtual file system.
+  ///
+  /// If any component in the file fails to load, the new entity is deleted and
+  /// an error is returned. As with any deleted entity, it is still considered
+  /// alive until `commit_entities()` is called.
+  pub fn spawn_prefab(
+    &mut self,
+    vfs: &vfs::Context,
+    path: impl AsRef<vfs::Path>,
+  ) -> Result<Entity, PrefabError> {
+    let path = path.as_ref();
+
+    let source = vfs
+      .read_to_string(path)
+      .map_err(|cause| PrefabError::ReadFailed { path: path.to_string(), cause })?;
+
+    self.spawn_prefab_from_str(&path.to_string(), &source)
+  }
+
+  /// Creates a new entity from prefab source code, using `path` to identify the
+  /// source in any returned errors.
+  fn spawn_prefab_from_str(&mut self, path: &str, source: &str) -> Result<Entity, PrefabError> {
+    let components: toml::value::Table = toml::from_str(source)
+      .map_err(|cause| PrefabError::ParseFailed { path: path.into(), cause })?;
+
+    let entity = self.entities().create();
+
+    for (name, value) in components {
+      let insert = match self.registry.get(&name).and_then(|entry| entry.insert_from_toml) {
+        Some(insert) => insert,
+
+        None => {
+          self.entities().delete(entity);
+
+          return Err(PrefabError::UnknownComponent { path: path.into(), name });
+        }
+      };
+
+      if let Err(cause) = insert(&mut self.world, entity, value) {
+        self.entities().delete(entity);
+
+        return Err(PrefabError::InvalidComponent { path: path.into(), name, cause });
+      }
+    }
+
+    Ok(entity)
+  }
+}
+
+/// Deserializes a component of type `C` and inserts it for the given entity.
+fn insert_from_toml<C>(
+  world: &mut specs::World,
+  entity: Entity,
+  value: toml::Value,
+) -> Result<(), toml::de::Error>
+where
+  C: Component + DeserializeOwned,
+{
+  let component: C = value.try_into()?;
+
+  let _ = world.write_storage::<C>().insert(entity, component);
+
+  Ok(())
+}
+
+/// An error that occurred while spawning an entity from a prefab file.
+#[derive(Debug)]
+pub enum PrefabError {
+  /// The prefab file could not be read.
+  ReadFailed { path: String, cause: io::Error },
+  /// The prefab file is not valid TOML.
+  ParseFailed { path: String, cause: toml::de::Error },
+  /// The prefab file contains a component name that is not registered.
+  UnknownComponent { path: String, name: String },
+  /// A component in the prefab file could not be deserialized.
+  InvalidComponent { path: String, name: String, cause: toml::de::Error },
+}
+
+impl std::error::Error for PrefabError {}
+
+impl fmt::Display for PrefabError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      PrefabError::ReadFailed { path, cause } => {
+        write!(f, "failed to read prefab {}: {}", path, cause)
+      }
+
+      PrefabError::ParseFailed { path, cause } => {
+        write!(f, "failed to parse prefab {}: {}", path, cause)
+      }
+
+      PrefabError::UnknownComponent { path, name } => {
+        write!(f, "unknown component `{}` in prefab {}", name, path)
+      }
+
+      PrefabError::InvalidComponent { path, name, cause } => {
+        write!(f, "invalid component `{}` in prefab {}: {}", name, path, cause)
+      }
+    }
+  }
+}
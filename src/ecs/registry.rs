@@ -0,0 +1,110 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use std::any::TypeId;
+use std::collections::HashMap;
+
+/// Inserts a component deserialized from a TOML value.
+pub(super) type InsertFromToml =
+  fn(&mut specs::World, Entity, toml::Value) -> Result<(), toml::de::Error>;
+
+/// Adds each entity with a component to a set of entities.
+pub(super) type CollectEntities = fn(&specs::World, &mut BitSet);
+
+/// Serializes each component of a type with the entity it belongs to.
+pub(super) type SaveComponents =
+  fn(&specs::World) -> Result<Vec<(Entity, serde_json::Value)>, serde_json::Error>;
+
+/// Inserts a component deserialized from a JSON value.
+pub(super) type LoadComponent =
+  fn(&mut specs::World, Entity, serde_json::Value) -> Result<(), serde_json::Error>;
+
+/// Describes a component of an entity for debugging, returning `None` if the
+/// entity does not have the component.
+pub(super) type InspectComponent = fn(&specs::World, Entity) -> Option<Inspected>;
+
+/// A registry of named component types that describes how each type can be
+/// loaded from or written to data files.
+#[derive(Default)]
+pub(super) struct ComponentRegistry {
+  entries: Vec<RegisteredComponent>,
+  indices: HashMap<String, usize>,
+}
+
+impl ComponentRegistry {
+  /// Returns the entry for the component type `C` with the given name,
+  /// creating it if it does not exist.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if the name is already registered to a different
+  /// component type.
+  pub fn entry<C: Component>(&mut self, name: &str) -> &mut RegisteredComponent {
+    let type_id = TypeId::of::<C>();
+
+    let index = match self.indices.get(name) {
+      Some(&index) => index,
+
+      None => {
+        self.entries.push(RegisteredComponent {
+          name: name.into(),
+          type_id,
+          insert_from_toml: None,
+          persistence: None,
+          inspect: None,
+        });
+
+        self.indices.insert(name.into(), self.entries.len() - 1);
+        self.entries.len() - 1
+      }
+    };
+
+    let entry = &mut self.entries[index];
+
+    assert!(
+      entry.type_id == type_id,
+      "component name `{}` is already registered to a different type",
+      name
+    );
+
+    entry
+  }
+
+  /// Returns the entry for the component with the given name if it exists.
+  pub fn get(&self, name: &str) -> Option<&RegisteredComponent> {
+    self.indices.get(name).map(|&index| &self.entries[index])
+  }
+
+  /// Returns an iterator over all entries in the order in which they were
+  /// registered.
+  pub fn iter(&self) -> impl Iterator<Item = &RegisteredComponent> {
+    self.entries.iter()
+  }
+}
+
+/// An entry in a `ComponentRegistry`.
+pub(super) struct RegisteredComponent {
+  pub name: String,
+  pub type_id: TypeId,
+  pub insert_from_toml: Option<InsertFromToml>,
+  pub persistence: Option<Persistence>,
+  pub inspect: Option<InspectComponent>,
+}
+
+/// Functions for saving and loading a persistent component type.
+#[derive(Clone, Copy)]
+pub(super) struct Persistence {
+  pub collect_entities: CollectEntities,
+  pub save: SaveComponents,
+  pub load: LoadComponent,
+}
+
+/// A component described for debugging by an `InspectComponent` function.
+pub(super) enum Inspected {
+  /// The `fmt::Debug` representation of the component.
+  Debug(String),
+  /// The serialized representation of the component.
+  Serialized(Result<serde_json::Value, serde_json::Error>),
+}
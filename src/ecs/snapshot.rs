@@ -0,0 +1,285 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::entity_ref::EntityMap;
+use super::registry::Persistence;
+use super::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// A function that migrates a snapshot from one version to the next.
+type Migration = Box<dyn Fn(&mut Snapshot) + Send + Sync>;
+
+/// A serializable snapshot of the persistent entities and components in an
+/// ECS context.
+///
+/// Components are stored as self-describing values keyed by their registered
+/// names, so a snapshot can be written with any serde format and migrated
+/// between versions without knowing the component types.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+  /// The format version of the snapshot.
+  pub version: u32,
+  /// The saved entities, in ascending order of ID.
+  pub entities: Vec<SnapshotEntity>,
+}
+
+/// An entity saved in a `Snapshot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntity {
+  /// The ID of the entity in the snapshot, used for entity references in
+  /// components.
+  pub id: u32,
+  /// The saved components of the entity, keyed by registered name.
+  pub components: BTreeMap<String, serde_json::Value>,
+}
+
+/// Snapshot versioning state of an ECS context.
+#[derive(Default)]
+pub(super) struct SnapshotVersioning {
+  version: u32,
+  migrations: HashMap<u32, Migration>,
+}
+
+impl Context {
+  /// Registers a component type that is saved in snapshots under the given
+  /// name.
+  ///
+  /// Components containing entity references should serialize them with the
+  /// functions in the `entity_ref` module.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if the name is already registered to a different
+  /// component type.
+  pub fn register_persistent_component<C>(&mut self, name: &str)
+  where
+    C: Component + Serialize + DeserializeOwned + Send,
+    C::Storage: Default,
+  {
+    self.register_component::<C>();
+
+    self.registry.entry::<C>(name).persistence = Some(Persistence {
+      collect_entities: collect_entities::<C>,
+      save: save_components::<C>,
+      load: load_component::<C>,
+    });
+  }
+
+  /// Returns the current snapshot format version, which is zero by default.
+  pub fn snapshot_version(&self) -> u32 {
+    self.snapshot_versioning.version
+  }
+
+  /// Sets the current snapshot format version.
+  ///
+  /// New snapshots are saved with this version, and older snapshots are
+  /// migrated to this version when they are restored.
+  pub fn set_snapshot_version(&mut self, version: u32) {
+    self.snapshot_versioning.version = version;
+  }
+
+  /// Registers a function that migrates snapshots from version `from` to
+  /// version `from + 1`.
+  ///
+  /// The function does not need to change the `version` field of the snapshot.
+  pub fn register_snapshot_migration(
+    &mut self,
+    from: u32,
+    migrate: impl Fn(&mut Snapshot) + Send + Sync + 'static,
+  ) {
+    self.snapshot_versioning.migrations.insert(from, Box::new(migrate));
+  }
+
+  /// Saves a snapshot of every entity with at least one persistent component.
+  pub fn snapshot(&self) -> Result<Snapshot, SnapshotError> {
+    let persistent: Vec<_> =
+      self.registry.iter().filter_map(|entry| Some((&entry.name, entry.persistence?))).collect();
+
+    // Assign snapshot IDs to each saved entity in order of entity index.
+    let mut saved = BitSet::new();
+
+    for (_, persistence) in &persistent {
+      (persistence.collect_entities)(&self.world, &mut saved);
+    }
+
+    let entities: Vec<Entity> =
+      (&*self.world.entities(), &saved).join().map(|(entity, _)| entity).collect();
+
+    let ids: HashMap<Entity, u32> =
+      entities.iter().enumerate().map(|(id, entity)| (*entity, id as u32)).collect();
+
+    let mut snapshot = Snapshot {
+      version: self.snapshot_versioning.version,
+      entities: (0..entities.len() as u32)
+        .map(|id| SnapshotEntity { id, components: BTreeMap::new() })
+        .collect(),
+    };
+
+    // Serialize all persistent components with entity references mapped to
+    // snapshot IDs.
+    let _guard = EntityMap::Save(ids.clone()).set();
+
+    for (name, persistence) in persistent {
+      let components = (persistence.save)(&self.world)
+        .map_err(|cause| SnapshotError::SerializeFailed { component: name.clone(), cause })?;
+
+      for (entity, value) in components {
+        snapshot.entities[ids[&entity] as usize].components.insert(name.clone(), value);
+      }
+    }
+
+    Ok(snapshot)
+  }
+
+  /// Restores the entities in a snapshot, migrating it to the current version
+  /// first if needed.
+  ///
+  /// Restored entities are created in addition to any existing entities.
+  /// Returns the new entities in the same order as the entities in the
+  /// snapshot. If an error occurs, all new entities are deleted. As with any
+  /// deleted entity, they are still considered alive until `commit_entities()`
+  /// is called.
+  pub fn restore(&mut self, mut snapshot: Snapshot) -> Result<Vec<Entity>, SnapshotError> {
+    self.migrate_snapshot(&mut snapshot)?;
+
+    let entities: Vec<Entity> =
+      snapshot.entities.iter().map(|_| self.entities().create()).collect();
+
+    let result = self.restore_components(snapshot, &entities);
+
+    if result.is_err() {
+      let all = self.entities();
+
+      for entity in &entities {
+        all.delete(*entity);
+      }
+    }
+
+    result.map(|_| entities)
+  }
+
+  /// Migrates a snapshot to the current snapshot version.
+  fn migrate_snapshot(&self, snapshot: &mut Snapshot) -> Result<(), SnapshotError> {
+    let current = self.snapshot_versioning.version;
+
+    if snapshot.version > current {
+      return Err(SnapshotError::UnsupportedVersion { version: snapshot.version, current });
+    }
+
+    while snapshot.version < current {
+      let migrate = self
+        .snapshot_versioning
+        .migrations
+        .get(&snapshot.version)
+        .ok_or(SnapshotError::MissingMigration { from: snapshot.version })?;
+
+      migrate(snapshot);
+
+      snapshot.version += 1;
+    }
+
+    Ok(())
+  }
+
+  /// Deserializes and inserts the components of each entity in a snapshot.
+  fn restore_components(
+    &mut self,
+    snapshot: Snapshot,
+    entities: &[Entity],
+  ) -> Result<(), SnapshotError> {
+    let map = snapshot.entities.iter().zip(entities).map(|(saved, entity)| (saved.id, *entity));
+    let _guard = EntityMap::Restore(map.collect()).set();
+
+    for (saved, entity) in snapshot.entities.into_iter().zip(entities) {
+      for (name, value) in saved.components {
+        let load = match self.registry.get(&name).and_then(|entry| entry.persistence) {
+          Some(persistence) => persistence.load,
+          None => return Err(SnapshotError::UnknownComponent { component: name }),
+        };
+
+        load(&mut self.world, *entity, value)
+          .map_err(|cause| SnapshotError::DeserializeFailed { component: name, cause })?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Adds each entity with a component of type `C` to a set.
+fn collect_entities<C: Component>(world: &specs::World, entities: &mut BitSet) {
+  for (entity, _) in (&*world.entities(), &world.read_storage::<C>()).join() {
+    entities.add(entity.id());
+  }
+}
+
+/// Serializes each component of type `C`.
+fn save_components<C: Component + Serialize>(
+  world: &specs::World,
+) -> Result<Vec<(Entity, serde_json::Value)>, serde_json::Error> {
+  (&*world.entities(), &world.read_storage::<C>())
+    .join()
+    .map(|(entity, component)| Ok((entity, serde_json::to_value(component)?)))
+    .collect()
+}
+
+/// Deserializes a component of type `C` and inserts it for the given entity.
+fn load_component<C: Component + DeserializeOwned>(
+  world: &mut specs::World,
+  entity: Entity,
+  value: serde_json::Value,
+) -> Result<(), serde_json::Error> {
+  let component: C = serde_json::from_value(value)?;
+
+  let _ = world.write_storage::<C>().insert(entity, component);
+
+  Ok(())
+}
+
+/// An error that occurred while saving or restoring a snapshot.
+#[derive(Debug)]
+pub enum SnapshotError {
+  /// A component could not be serialized.
+  SerializeFailed { component: String, cause: serde_json::Error },
+  /// A component could not be deserialized.
+  DeserializeFailed { component: String, cause: serde_json::Error },
+  /// The snapshot contains a component name that is not registered as
+  /// persistent.
+  UnknownComponent { component: String },
+  /// The snapshot version is newer than the current version.
+  UnsupportedVersion { version: u32, current: u32 },
+  /// No migration is registered from a version older than the current
+  /// version.
+  MissingMigration { from: u32 },
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl fmt::Display for SnapshotError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SnapshotError::SerializeFailed { component, cause } => {
+        write!(f, "failed to serialize component `{}`: {}", component, cause)
+      }
+
+      SnapshotError::DeserializeFailed { component, cause } => {
+        write!(f, "failed to deserialize component `{}`: {}", component, cause)
+      }
+
+      SnapshotError::UnknownComponent { component } => {
+        write!(f, "unknown persistent component `{}`", component)
+      }
+
+      SnapshotError::UnsupportedVersion { version, current } => {
+        write!(f, "snapshot version {} is newer than the current version {}", version, current)
+      }
+
+      SnapshotError::MissingMigration { from } => {
+        write!(f, "no migration from snapshot version {}", from)
+      }
+    }
+  }
+}
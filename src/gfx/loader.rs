@@ -44,6 +44,8 @@ impl Loader {
     let (send_result, recv_result) = channel::bounded(0);
 
     thread_scope.spawn(move |thread_scope| {
+      time::set_profile_thread_name("loader");
+
       // Initialize the loader state.
       let mut state = match LoaderState::new(&context) {
         Ok(state) => state,
@@ -68,6 +70,8 @@ impl Loader {
       while let Ok(message) = recv_messages.recv() {
         match message {
           Message::LoadBuffer { src, kind, result } => {
+            crate::profile_scope!("load_buffer");
+
             let src = (*src).as_ref();
 
             // Create the buffer to load data into.
@@ -111,6 +115,8 @@ impl Loader {
           }
 
           Message::LoadImage { src, size, result } => {
+            crate::profile_scope!("load_image");
+
             let src = (*src).as_ref();
 
             // Create the image to load data into.
@@ -21,7 +21,7 @@ mod shader;
 mod surface;
 mod vertex;
 
-pub use self::context::*;
+pub use self::{color::Color, context::*};
 
 use self::{
   buffer::*, color::*, descriptors::*, framebuffer::*, image::*, memory::*, render_pass::*,
@@ -30,12 +30,16 @@ pub fn start(
   thread_scope.spawn(move |_| {
     let cmd_pool = cmd_pool.into_ref_cell();
 
+    time::set_profile_thread_name("renderer");
+
     // Run the renderer indefinitely (until the window is closed).
     log::info!(&logger, "renderer started");
 
     loop {
       // Render a single frame or exit the loop on failure.
       let mut render = || -> Result<(), RenderError> {
+        crate::profile_scope!("render_frame");
+
         let backbuffer = surface.acquire(&acquire_semaphore)?;
 
         framebuffer.set_attachment(backbuffer.image());
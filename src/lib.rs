@@ -7,6 +7,8 @@ pub mod ecs;
 pub mod gfx;
 pub mod log;
 pub mod math;
+pub mod pathfinding;
+pub mod physics;
 pub mod time;
 pub mod util;
 pub mod vfs;
@@ -0,0 +1,204 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use slog::KV as _;
+use std::collections::VecDeque;
+use std::sync::{MutexGuard, PoisonError};
+
+/// A log drain that keeps the most recent records in memory.
+///
+/// Cloning a `Capture` returns a new handle to the same records, so one handle
+/// can be given to a `Logger` while another is used to read the records back,
+/// for example in tests or an in-game console.
+///
+/// The buffer uses a standard mutex, which makes `Capture` unwind safe as a
+/// `Logger` requires.
+#[derive(Clone)]
+pub struct Capture {
+  buffer: Arc<std::sync::Mutex<CaptureBuffer>>,
+}
+
+/// A log record kept by a `Capture`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedRecord {
+  /// Level of the record.
+  pub level: Level,
+  /// Formatted message of the record.
+  pub message: String,
+  /// Path of the module the record was logged from.
+  pub module: &'static str,
+  /// Key-value pairs of the record followed by those of its logger, formatted
+  /// as strings.
+  pub values: Vec<(String, String)>,
+}
+
+struct CaptureBuffer {
+  records: VecDeque<CapturedRecord>,
+  capacity: usize,
+}
+
+impl Capture {
+  /// Creates a new capture that keeps at most `capacity` records.
+  pub fn new(capacity: usize) -> Self {
+    Capture {
+      buffer: Arc::new(std::sync::Mutex::new(CaptureBuffer {
+        records: VecDeque::with_capacity(capacity),
+        capacity,
+      })),
+    }
+  }
+
+  /// Returns the maximum number of records kept.
+  pub fn capacity(&self) -> usize {
+    self.lock().capacity
+  }
+
+  /// Returns the number of records currently kept.
+  pub fn len(&self) -> usize {
+    self.lock().records.len()
+  }
+
+  /// Returns `true` if no records are currently kept.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Returns a copy of the kept records, oldest first.
+  pub fn records(&self) -> Vec<CapturedRecord> {
+    self.lock().records.iter().cloned().collect()
+  }
+
+  /// Removes and returns the kept records, oldest first.
+  pub fn take(&self) -> Vec<CapturedRecord> {
+    self.lock().records.drain(..).collect()
+  }
+
+  /// Removes all kept records.
+  pub fn clear(&self) {
+    self.lock().records.clear();
+  }
+
+  /// Locks the buffer.
+  ///
+  /// The buffer is never left in an inconsistent state, so it is still used
+  /// after a panic poisons the lock.
+  fn lock(&self) -> MutexGuard<CaptureBuffer> {
+    self.buffer.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+}
+
+impl fmt::Debug for Capture {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let buffer = self.lock();
+
+    f.debug_struct("Capture")
+      .field("len", &buffer.records.len())
+      .field("capacity", &buffer.capacity)
+      .finish()
+  }
+}
+
+impl Drain for Capture {
+  type Ok = ();
+  type Err = slog::Never;
+
+  fn log(&self, record: &Record, values: &slog::OwnedKVList) -> Result<(), slog::Never> {
+    let mut collector = ValueCollector(Vec::new());
+
+    // Serialization into strings cannot fail.
+    let _ = record.kv().serialize(record, &mut collector);
+    let _ = values.serialize(record, &mut collector);
+
+    let captured = CapturedRecord {
+      level: record.level(),
+      message: record.msg().to_string(),
+      module: record.module(),
+      values: collector.0,
+    };
+
+    let mut buffer = self.lock();
+
+    if buffer.capacity == 0 {
+      return Ok(());
+    }
+
+    while buffer.records.len() >= buffer.capacity {
+      buffer.records.pop_front();
+    }
+
+    buffer.records.push_back(captured);
+
+    Ok(())
+  }
+}
+
+/// A serializer that formats each key-value pair as a pair of strings.
+struct ValueCollector(Vec<(String, String)>);
+
+impl Serializer for ValueCollector {
+  fn emit_arguments(&mut self, key: Key, value: &fmt::Arguments) -> SerializationResult {
+    self.0.push((key.to_string(), value.to_string()));
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn captures_records_logged_to_a_logger() {
+    let capture = Capture::new(8);
+    let logger = Logger::root(capture.clone(), o!("scope" => "test"));
+
+    warn!(logger, "loaded {} files", 3; "path" => "assets", "count" => 3);
+
+    let mut records = capture.take();
+
+    assert_eq!(records.len(), 1);
+    assert!(capture.is_empty());
+
+    let record = records.remove(0);
+
+    assert_eq!(record.level, Level::Warning);
+    assert_eq!(record.message, "loaded 3 files");
+    assert_eq!(record.module, module_path!());
+
+    // Record values come before logger values, but slog does not define the
+    // order within each group.
+    let mut values = record.values[..2].to_vec();
+
+    values.sort();
+
+    assert_eq!(values, vec![("count".into(), "3".into()), ("path".into(), "assets".into())]);
+    assert_eq!(record.values[2..], [("scope".to_string(), "test".to_string())]);
+  }
+
+  #[test]
+  fn drops_oldest_records_at_capacity() {
+    let capture = Capture::new(2);
+    let logger = Logger::root(capture.clone(), o!());
+
+    for index in 0..5 {
+      info!(logger, "record {}", index);
+    }
+
+    let messages: Vec<_> = capture.records().into_iter().map(|record| record.message).collect();
+
+    assert_eq!(capture.len(), 2);
+    assert_eq!(messages, vec!["record 3", "record 4"]);
+  }
+
+  #[test]
+  fn keeps_nothing_with_zero_capacity() {
+    let capture = Capture::new(0);
+    let logger = Logger::root(capture.clone(), o!());
+
+    info!(logger, "dropped");
+
+    assert!(capture.is_empty());
+  }
+}
@@ -0,0 +1,113 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// A log file writer that rotates the file once it reaches a maximum size.
+///
+/// The size is checked each time the writer is flushed, which log drains do
+/// after every record.
+///
+/// When the file is rotated, it is renamed to `<path>.1` and any existing
+/// rotated files are shifted up by one. At most `max_files` rotated files are
+/// kept.
+#[derive(Debug)]
+pub struct RotatingFile {
+  path: PathBuf,
+  file: File,
+  size: u64,
+  max_size: u64,
+  max_files: usize,
+}
+
+impl RotatingFile {
+  /// Opens the file at the given path for appending, creating it and any
+  /// parent directories if they do not exist.
+  pub fn open(path: impl Into<PathBuf>, max_size: u64, max_files: usize) -> io::Result<Self> {
+    let path = path.into();
+
+    if let Some(parent) = path.parent() {
+      if !parent.as_os_str().is_empty() {
+        fs::create_dir_all(parent)?;
+      }
+    }
+
+    let file = open_append(&path)?;
+    let size = file.metadata()?.len();
+
+    Ok(RotatingFile { path, file, size, max_size, max_files })
+  }
+
+  /// Returns the path of the current log file.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Renames the current file and any previously rotated files, then opens a
+  /// new empty file.
+  pub fn rotate(&mut self) -> io::Result<()> {
+    self.file.flush()?;
+
+    if self.max_files == 0 {
+      // Nothing is kept, so just truncate the current file.
+      self.file = File::create(&self.path)?;
+      self.size = 0;
+
+      return Ok(());
+    }
+
+    let _ = fs::remove_file(self.rotated_path(self.max_files));
+
+    for index in (1..self.max_files).rev() {
+      let from = self.rotated_path(index);
+
+      if from.exists() {
+        fs::rename(&from, self.rotated_path(index + 1))?;
+      }
+    }
+
+    fs::rename(&self.path, self.rotated_path(1))?;
+
+    self.file = open_append(&self.path)?;
+    self.size = 0;
+
+    Ok(())
+  }
+
+  /// Returns the path of the rotated file with the given index.
+  fn rotated_path(&self, index: usize) -> PathBuf {
+    let mut path = self.path.clone().into_os_string();
+
+    path.push(format!(".{}", index));
+    path.into()
+  }
+}
+
+impl Write for RotatingFile {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let written = self.file.write(buf)?;
+
+    self.size += written as u64;
+
+    Ok(written)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.file.flush()?;
+
+    // Drains flush after each record, so rotating here never splits a record
+    // across two files.
+    if self.size >= self.max_size {
+      self.rotate()?;
+    }
+
+    Ok(())
+  }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+  OpenOptions::new().create(true).append(true).open(path)
+}
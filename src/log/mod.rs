@@ -2,17 +2,24 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+mod capture;
+mod file;
+mod settings;
+
+pub use self::{capture::*, file::*, settings::*};
 pub use slog::{
   b, crit, debug, error, info, o, trace, warn, Drain, Error as SerializationError, Key, Level,
   Logger, Record, Result as SerializationResult, Serializer, Value,
 };
 
 use super::*;
+use std::io;
 
 lazy_static! {
   static ref GUARDS: Mutex<Option<(slog_scope::GlobalLoggerGuard, slog_async::AsyncGuard)>> =
     Mutex::new(None);
   static ref LOGGER: RwLock<Option<Logger>> = RwLock::new(None);
+  static ref CAPTURE: RwLock<Option<Capture>> = RwLock::new(None);
 }
 
 /// A struct wrapper for log values that formats the value with `fmt::Debug`.
@@ -43,15 +50,69 @@ impl<T: fmt::Display> Value for Display<T> {
   }
 }
 
-/// Initializes the logging module.
+/// A boxed drain that outputs log records to one destination.
+type BoxedDrain = Box<dyn Drain<Ok = (), Err = slog::Never> + Send>;
+
+/// A drain that filters log records by level depending on their module.
+struct ModuleFilter<D> {
+  drain: D,
+  settings: Settings,
+}
+
+impl<D: Drain> Drain for ModuleFilter<D> {
+  type Ok = ();
+  type Err = D::Err;
+
+  fn log(&self, record: &Record, values: &slog::OwnedKVList) -> Result<(), D::Err> {
+    if self.settings.level_for(record.module()).allows(record.level()) {
+      self.drain.log(record, values)?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Initializes the logging module with default settings.
 pub fn init() {
-  let decorator = slog_term::term_full();
-  let (drain, async_guard) = slog_async::Async::new(decorator.fuse()).build_with_guard();
+  init_with(&Settings::default()).expect("failed to initialize logging");
+}
+
+/// Initializes the logging module with the given settings.
+///
+/// Returns an error if the log file could not be opened.
+pub fn init_with(settings: &Settings) -> io::Result<()> {
+  let mut drain = match settings.format {
+    Format::Text => text_drain(slog_term::TermDecorator::new().build()),
+    Format::Json => json_drain(io::stdout()),
+  };
+
+  if let Some(file) = &settings.file {
+    let writer = RotatingFile::open(&file.path, file.max_size, file.max_files)?;
+
+    let file_drain = match file.format {
+      Format::Text => text_drain(slog_term::PlainSyncDecorator::new(writer)),
+      Format::Json => json_drain(writer),
+    };
+
+    drain = Box::new(slog::Duplicate::new(drain, file_drain).ignore_res());
+  }
+
+  // Keep recent records in memory if requested.
+  let capture = settings.capture.map(Capture::new);
+
+  if let Some(capture) = &capture {
+    drain = Box::new(slog::Duplicate::new(drain, capture.clone()).ignore_res());
+  }
+
+  let filter = ModuleFilter { drain, settings: settings.clone() };
+  let (drain, async_guard) = slog_async::Async::new(filter.fuse()).build_with_guard();
   let logger = slog::Logger::root(drain.fuse(), o!());
 
   // Expose the global logger to users of the `log` and `slog_scope` crates.
+  let external_level = settings.external_level;
+
   let global_guard = slog_scope::set_global_logger(slog::Logger::root(
-    logger.clone().filter_level(Level::Warning).fuse(),
+    logger.clone().filter(move |record| external_level.allows(record.level())).fuse(),
     o!(),
   ));
 
@@ -59,7 +120,30 @@ pub fn init() {
 
   // Store the logger and guard.
   *LOGGER.write() = Some(logger.clone());
+  *CAPTURE.write() = capture;
   *GUARDS.lock() = Some((global_guard, async_guard));
+
+  Ok(())
+}
+
+/// Creates a drain that writes human-readable records with the given
+/// decorator.
+fn text_drain<D>(decorator: D) -> BoxedDrain
+where
+  D: slog_term::Decorator + Send + 'static,
+{
+  Box::new(slog_term::FullFormat::new(decorator).build().ignore_res())
+}
+
+/// Creates a drain that writes one JSON object per record to the given writer.
+fn json_drain<W>(writer: W) -> BoxedDrain
+where
+  W: io::Write + Send + 'static,
+{
+  Box::new(
+    std::sync::Mutex::new(slog_json::Json::new(writer).set_flush(true).add_default_keys().build())
+      .ignore_res(),
+  )
 }
 
 /// Returns a new `Logger` based on the default.
@@ -67,6 +151,15 @@ pub fn logger() -> Logger {
   LOGGER.read().as_ref().cloned().expect("log::init has not been called")
 }
 
+/// Returns a handle to the records captured in memory, or `None` if capture
+/// was not enabled in the settings passed to `init_with`.
+///
+/// Records are captured asynchronously, so the most recent records may not be
+/// available immediately after they are logged.
+pub fn capture() -> Option<Capture> {
+  CAPTURE.read().as_ref().cloned()
+}
+
 /// Flushes log records and shuts down the logging module.
 ///
 /// This function should be called before exiting the program to ensure that all
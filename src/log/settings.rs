@@ -0,0 +1,166 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Default maximum size of a log file before it is rotated, in bytes.
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Default number of rotated log files to keep.
+pub const DEFAULT_MAX_FILES: usize = 5;
+
+/// Logging configuration options.
+///
+/// These are usually read from the `[log]` section of the application's TOML
+/// settings file and passed to `log::init_with`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+  /// Minimum level of records to output from modules without a more specific
+  /// filter in `modules`.
+  ///
+  /// Defaults to `trace`, which outputs every record that was not disabled at
+  /// compile time.
+  #[serde(default = "default_level")]
+  pub level: LevelFilter,
+  /// Minimum level of records to output for specific modules, keyed by module
+  /// path such as `nova::gfx`.
+  ///
+  /// The longest matching module path takes precedence.
+  #[serde(default)]
+  pub modules: BTreeMap<String, LevelFilter>,
+  /// Minimum level of records to forward to the global `slog_scope` logger,
+  /// which is used by the `log` crate and other libraries.
+  #[serde(default = "default_external_level")]
+  pub external_level: LevelFilter,
+  /// Format of records written to the terminal.
+  #[serde(default)]
+  pub format: Format,
+  /// Optional file to also write records to.
+  #[serde(default)]
+  pub file: Option<FileSettings>,
+  /// Number of recent records to keep in memory, which can then be read with
+  /// `log::capture()`.
+  #[serde(default)]
+  pub capture: Option<usize>,
+}
+
+impl Settings {
+  /// Returns the level filter that applies to the given module path.
+  pub fn level_for(&self, module: &str) -> LevelFilter {
+    let mut best: Option<(&str, LevelFilter)> = None;
+
+    for (path, &level) in &self.modules {
+      let matches = module == path
+        || (module.starts_with(path.as_str()) && module[path.len()..].starts_with("::"));
+
+      if matches && best.map(|(p, _)| p.len() < path.len()).unwrap_or(true) {
+        best = Some((path, level));
+      }
+    }
+
+    best.map(|(_, level)| level).unwrap_or(self.level)
+  }
+}
+
+impl Default for Settings {
+  fn default() -> Self {
+    Settings {
+      level: default_level(),
+      modules: BTreeMap::new(),
+      external_level: default_external_level(),
+      format: Format::default(),
+      file: None,
+      capture: None,
+    }
+  }
+}
+
+/// Options for writing log records to a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSettings {
+  /// Path of the log file.
+  pub path: PathBuf,
+  /// Format of records written to the file.
+  #[serde(default)]
+  pub format: Format,
+  /// Maximum size of the log file in bytes before it is rotated.
+  #[serde(default = "default_max_file_size")]
+  pub max_size: u64,
+  /// Number of rotated files to keep. Rotated files are named by appending
+  /// `.1`, `.2`, and so on to the path, with `.1` being the most recent.
+  #[serde(default = "default_max_files")]
+  pub max_files: usize,
+}
+
+/// Output format of log records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+  /// Human-readable text, one record per line.
+  Text,
+  /// One JSON object per line, for consumption by tools.
+  Json,
+}
+
+impl Default for Format {
+  fn default() -> Self {
+    Format::Text
+  }
+}
+
+/// The minimum level of log records to output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LevelFilter {
+  /// Output no records.
+  Off,
+  Critical,
+  Error,
+  Warning,
+  Info,
+  Debug,
+  Trace,
+}
+
+impl LevelFilter {
+  /// Returns `true` if records of the given level pass this filter.
+  pub fn allows(self, level: Level) -> bool {
+    match self.to_level() {
+      Some(min) => level.is_at_least(min),
+      None => false,
+    }
+  }
+
+  /// Returns the least severe level allowed by this filter, or `None` if it
+  /// allows no records.
+  pub fn to_level(self) -> Option<Level> {
+    match self {
+      LevelFilter::Off => None,
+      LevelFilter::Critical => Some(Level::Critical),
+      LevelFilter::Error => Some(Level::Error),
+      LevelFilter::Warning => Some(Level::Warning),
+      LevelFilter::Info => Some(Level::Info),
+      LevelFilter::Debug => Some(Level::Debug),
+      LevelFilter::Trace => Some(Level::Trace),
+    }
+  }
+}
+
+fn default_level() -> LevelFilter {
+  LevelFilter::Trace
+}
+
+fn default_external_level() -> LevelFilter {
+  LevelFilter::Warning
+}
+
+fn default_max_file_size() -> u64 {
+  DEFAULT_MAX_FILE_SIZE
+}
+
+fn default_max_files() -> usize {
+  DEFAULT_MAX_FILES
+}
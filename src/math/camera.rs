@@ -0,0 +1,247 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use std::f32::consts::PI;
+
+/// A two-dimensional camera for top-down games.
+///
+/// The camera looks at `position` in world space and draws it at the center of
+/// `viewport`, which is in screen pixels. One world unit is `zoom` pixels
+/// wide, and the view is rotated counter-clockwise by `rotation` radians.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Camera2D {
+  /// World position at the center of the viewport.
+  pub position: Point2<f32>,
+  /// Number of screen pixels per world unit.
+  pub zoom: f32,
+  /// Rotation of the camera in radians.
+  pub rotation: f32,
+  /// Area of the screen the camera draws to, in pixels.
+  pub viewport: Rect<f32>,
+  /// Size of the area around `position`, in world units, that a followed
+  /// target can move within without moving the camera.
+  pub deadzone: Option<Size<f32>>,
+  /// Area of the world, in world units, that the visible area is kept within
+  /// when following a target.
+  pub bounds: Option<Rect<f32>>,
+  shake: Option<Shake>,
+}
+
+/// State of an active screen shake.
+#[derive(Debug, Clone, PartialEq)]
+struct Shake {
+  intensity: f32,
+  frequency: f32,
+  duration: f64,
+  elapsed: f64,
+}
+
+impl Camera2D {
+  /// Creates a new camera drawing to the given viewport and looking at the
+  /// world origin with no zoom or rotation.
+  pub fn new(viewport: Rect<f32>) -> Self {
+    Camera2D {
+      position: Point2::origin(),
+      zoom: 1.0,
+      rotation: 0.0,
+      viewport,
+      deadzone: None,
+      bounds: None,
+      shake: None,
+    }
+  }
+
+  /// Returns the world position the camera is drawn from, which is `position`
+  /// plus the current screen shake offset.
+  pub fn eye(&self) -> Point2<f32> {
+    self.position + self.shake_offset()
+  }
+
+  /// Returns a matrix that transforms world coordinates into screen pixel
+  /// coordinates.
+  pub fn view(&self) -> Matrix4<f32> {
+    let center = self.viewport.center();
+    let eye = self.eye();
+
+    Matrix4::new_translation(&Vector3::new(center.x, center.y, 0.0))
+      * Matrix4::new_nonuniform_scaling(&Vector3::new(self.zoom, self.zoom, 1.0))
+      * Matrix4::new_rotation(Vector3::new(0.0, 0.0, -self.rotation))
+      * Matrix4::new_translation(&Vector3::new(-eye.x, -eye.y, 0.0))
+  }
+
+  /// Returns a matrix that transforms screen pixel coordinates in the
+  /// viewport into normalized device coordinates.
+  pub fn projection(&self) -> Matrix4<f32> {
+    Matrix4::new_orthographic(
+      self.viewport.start.x,
+      self.viewport.end.x,
+      self.viewport.start.y,
+      self.viewport.end.y,
+      -1.0,
+      1.0,
+    )
+  }
+
+  /// Returns a matrix that transforms world coordinates into normalized device
+  /// coordinates.
+  pub fn view_projection(&self) -> Matrix4<f32> {
+    self.projection() * self.view()
+  }
+
+  /// Converts a point in world coordinates to screen pixel coordinates.
+  pub fn world_to_screen(&self, point: Point2<f32>) -> Point2<f32> {
+    let offset = rotate(point - self.eye(), -self.rotation) * self.zoom;
+
+    self.viewport.center() + offset
+  }
+
+  /// Converts a point in screen pixel coordinates to world coordinates.
+  pub fn screen_to_world(&self, point: Point2<f32>) -> Point2<f32> {
+    let offset = rotate((point - self.viewport.center()) / self.zoom, self.rotation);
+
+    self.eye() + offset
+  }
+
+  /// Returns the size of the viewport in world units.
+  pub fn view_size(&self) -> Size<f32> {
+    self.viewport.size() / self.zoom
+  }
+
+  /// Returns the smallest world rectangle containing everything visible
+  /// through the camera.
+  pub fn visible_rect(&self) -> Rect<f32> {
+    let corners = [
+      self.screen_to_world(self.viewport.start),
+      self.screen_to_world(Point2::new(self.viewport.end.x, self.viewport.start.y)),
+      self.screen_to_world(self.viewport.end),
+      self.screen_to_world(Point2::new(self.viewport.start.x, self.viewport.end.y)),
+    ];
+
+    let mut rect = Rect::new(corners[0], corners[0]);
+
+    for corner in &corners[1..] {
+      rect.start.x = rect.start.x.min(corner.x);
+      rect.start.y = rect.start.y.min(corner.y);
+      rect.end.x = rect.end.x.max(corner.x);
+      rect.end.y = rect.end.y.max(corner.y);
+    }
+
+    rect
+  }
+
+  /// Moves the camera toward a target position.
+  ///
+  /// If the camera has a deadzone, the camera only moves far enough to bring
+  /// the target back inside it. The `smoothing` value is the fraction of the
+  /// remaining distance to move, from `0.0` (don't move) to `1.0` (move all the
+  /// way). The result is then clamped to the camera's bounds.
+  pub fn follow(&mut self, target: Point2<f32>, smoothing: f32) {
+    let mut goal = target;
+
+    if let Some(deadzone) = self.deadzone {
+      let half = deadzone / 2.0;
+
+      goal.x = self.position.x + excess(target.x - self.position.x, half.width);
+      goal.y = self.position.y + excess(target.y - self.position.y, half.height);
+    }
+
+    self.position += (goal - self.position) * clamp(smoothing, 0.0..=1.0);
+    self.clamp_to_bounds();
+  }
+
+  /// Moves the camera so that the visible area stays within its bounds.
+  ///
+  /// If the bounds are smaller than the visible area in either dimension, the
+  /// camera is centered on the bounds in that dimension. Rotation is ignored.
+  pub fn clamp_to_bounds(&mut self) {
+    let bounds = match self.bounds {
+      Some(bounds) => bounds,
+      None => return,
+    };
+
+    let half = self.view_size() / 2.0;
+
+    self.position.x = clamp_axis(self.position.x, bounds.start.x, bounds.end.x, half.width);
+    self.position.y = clamp_axis(self.position.y, bounds.start.y, bounds.end.y, half.height);
+  }
+
+  /// Starts shaking the camera.
+  ///
+  /// The camera is offset up to `intensity` world units, oscillating
+  /// `frequency` times per second and fading out over `duration`. The shake is
+  /// deterministic, so replaying the same updates produces the same offsets.
+  pub fn shake(&mut self, intensity: f32, frequency: f32, duration: time::Duration) {
+    self.shake = Some(Shake { intensity, frequency, duration: duration.as_secs(), elapsed: 0.0 });
+  }
+
+  /// Returns `true` if the camera is currently shaking.
+  pub fn is_shaking(&self) -> bool {
+    self.shake.is_some()
+  }
+
+  /// Stops shaking the camera immediately.
+  pub fn stop_shake(&mut self) {
+    self.shake = None;
+  }
+
+  /// Advances the camera's screen shake by the given amount of time.
+  pub fn update(&mut self, delta: time::Duration) {
+    if let Some(shake) = &mut self.shake {
+      shake.elapsed += delta.as_secs();
+
+      if shake.elapsed >= shake.duration {
+        self.shake = None;
+      }
+    }
+  }
+
+  /// Returns the current screen shake offset in world units.
+  pub fn shake_offset(&self) -> Vector2<f32> {
+    let shake = match &self.shake {
+      Some(shake) if shake.duration > 0.0 => shake,
+      _ => return Vector2::zeros(),
+    };
+
+    let t = shake.elapsed as f32;
+    let fade = 1.0 - (shake.elapsed / shake.duration) as f32;
+    let phase = t * shake.frequency * 2.0 * PI;
+
+    // Use two incommensurate waves per axis so the motion doesn't look like a
+    // simple circle.
+    let x = (phase.sin() + (phase * 1.7 + 0.5).sin()) / 2.0;
+    let y = (phase.cos() + (phase * 2.3 + 1.3).sin()) / 2.0;
+
+    Vector2::new(x, y) * shake.intensity * fade
+  }
+}
+
+/// Rotates a vector counter-clockwise by the given angle in radians.
+fn rotate(vector: Vector2<f32>, angle: f32) -> Vector2<f32> {
+  let (sin, cos) = angle.sin_cos();
+
+  Vector2::new(vector.x * cos - vector.y * sin, vector.x * sin + vector.y * cos)
+}
+
+/// Returns how far `offset` is outside of `-half..=half`, or zero if it is
+/// inside.
+fn excess(offset: f32, half: f32) -> f32 {
+  if offset > half {
+    offset - half
+  } else if offset < -half {
+    offset + half
+  } else {
+    0.0
+  }
+}
+
+/// Clamps a camera position on one axis so that a view extending `half` in
+/// each direction stays between `start` and `end`.
+fn clamp_axis(position: f32, start: f32, end: f32, half: f32) -> f32 {
+  if end - start <= half * 2.0 {
+    (start + end) / 2.0
+  } else {
+    clamp(position, start + half..=end - half)
+  }
+}
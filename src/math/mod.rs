@@ -5,11 +5,15 @@
 //! The `math` module exposes common functions and types for working with
 //! scalar numbers, vectors, matrices, and geometric dimensions.
 
+mod camera;
 mod rect;
 mod size;
+mod spatial_grid;
 
+pub use self::camera::Camera2D;
 pub use self::rect::Rect;
 pub use self::size::Size;
+pub use self::spatial_grid::*;
 pub use nalgebra::Matrix4;
 pub use nalgebra::Scalar;
 pub use nalgebra::{Point2, Point3};
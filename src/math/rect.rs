@@ -2,9 +2,14 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use super::{Point2, ScalarNum};
+use super::{clamp, Point2, ScalarNum, Size, Vector2};
+use num_traits::Signed;
+use std::fmt;
 
 /// Represents a two-dimensional rectangle in space.
+///
+/// A rectangle contains the points from its `start` up to but not including
+/// its `end`, so adjacent rectangles that share an edge do not intersect.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rect<T: ScalarNum> {
@@ -16,6 +21,36 @@ pub struct Rect<T: ScalarNum> {
 }
 
 impl<T: ScalarNum> Rect<T> {
+  /// Creates a new rectangle from its start and end points.
+  pub fn new(start: Point2<T>, end: Point2<T>) -> Self {
+    Rect { start, end }
+  }
+
+  /// Creates a new rectangle with the given start point and size.
+  pub fn with_size(start: Point2<T>, size: Size<T>) -> Self {
+    Rect { start, end: Point2::new(start.x + size.width, start.y + size.height) }
+  }
+
+  /// Returns the x coordinate of the start of the rectangle, its left edge.
+  pub fn x1(&self) -> T {
+    self.start.x
+  }
+
+  /// Returns the y coordinate of the start of the rectangle, its top edge.
+  pub fn y1(&self) -> T {
+    self.start.y
+  }
+
+  /// Returns the x coordinate of the end of the rectangle, its right edge.
+  pub fn x2(&self) -> T {
+    self.end.x
+  }
+
+  /// Returns the y coordinate of the end of the rectangle, its bottom edge.
+  pub fn y2(&self) -> T {
+    self.end.y
+  }
+
   /// Returns the width of the rectangle.
   pub fn width(&self) -> T {
     self.end.x - self.start.x
@@ -25,4 +60,316 @@ impl<T: ScalarNum> Rect<T> {
   pub fn height(&self) -> T {
     self.end.y - self.start.y
   }
+
+  /// Returns the size of the rectangle.
+  pub fn size(&self) -> Size<T> {
+    Size::new(self.width(), self.height())
+  }
+
+  /// Returns the center point of the rectangle.
+  pub fn center(&self) -> Point2<T> {
+    let two = T::one() + T::one();
+
+    Point2::new(self.start.x + self.width() / two, self.start.y + self.height() / two)
+  }
+
+  /// Returns a copy of the rectangle moved by the given offset.
+  pub fn translate(&self, offset: Vector2<T>) -> Self {
+    Rect {
+      start: Point2::new(self.start.x + offset.x, self.start.y + offset.y),
+      end: Point2::new(self.end.x + offset.x, self.end.y + offset.y),
+    }
+  }
+}
+
+impl<T: ScalarNum + PartialOrd> Rect<T> {
+  /// Returns `true` if the rectangle has no area.
+  pub fn is_empty(&self) -> bool {
+    self.end.x <= self.start.x || self.end.y <= self.start.y
+  }
+
+  /// Returns `true` if the given point is inside the rectangle.
+  pub fn contains_point(&self, point: Point2<T>) -> bool {
+    point.x >= self.start.x
+      && point.x < self.end.x
+      && point.y >= self.start.y
+      && point.y < self.end.y
+  }
+
+  /// Returns `true` if the rectangle overlaps the given rectangle.
+  ///
+  /// Empty rectangles contain no points, so they never intersect anything.
+  pub fn intersects(&self, other: &Self) -> bool {
+    !self.is_empty()
+      && !other.is_empty()
+      && self.start.x < other.end.x
+      && other.start.x < self.end.x
+      && self.start.y < other.end.y
+      && other.start.y < self.end.y
+  }
+
+  /// Returns the overlapping area of the rectangle and the given rectangle, or
+  /// `None` if they do not overlap.
+  pub fn intersection(&self, other: &Self) -> Option<Self> {
+    let rect = Rect {
+      start: Point2::new(max(self.start.x, other.start.x), max(self.start.y, other.start.y)),
+      end: Point2::new(min(self.end.x, other.end.x), min(self.end.y, other.end.y)),
+    };
+
+    if rect.is_empty() {
+      None
+    } else {
+      Some(rect)
+    }
+  }
+
+  /// Returns the smallest rectangle that contains both the rectangle and the
+  /// given rectangle.
+  ///
+  /// Empty rectangles are ignored.
+  pub fn union(&self, other: &Self) -> Self {
+    if self.is_empty() {
+      return *other;
+    }
+
+    if other.is_empty() {
+      return *self;
+    }
+
+    Rect {
+      start: Point2::new(min(self.start.x, other.start.x), min(self.start.y, other.start.y)),
+      end: Point2::new(max(self.end.x, other.end.x), max(self.end.y, other.end.y)),
+    }
+  }
+
+  /// Returns the point within the rectangle that is closest to the given
+  /// point.
+  ///
+  /// Because the end of the rectangle is exclusive, the result may be on the
+  /// end edge. Empty rectangles return their start point.
+  pub fn clamp_point(&self, point: Point2<T>) -> Point2<T> {
+    if self.is_empty() {
+      return self.start;
+    }
+
+    Point2::new(
+      clamp(point.x, self.start.x..=self.end.x),
+      clamp(point.y, self.start.y..=self.end.y),
+    )
+  }
+}
+
+impl<T: ScalarNum + PartialOrd + Signed> Rect<T> {
+  /// Returns a copy of the rectangle grown by the given amount on every side.
+  ///
+  /// The rectangle can be shrunk with a negative amount, but the result never
+  /// has a negative size.
+  pub fn inflate(&self, amount: T) -> Self {
+    let start = Point2::new(self.start.x - amount, self.start.y - amount);
+    let end = Point2::new(self.end.x + amount, self.end.y + amount);
+
+    Rect { start, end: Point2::new(max(start.x, end.x), max(start.y, end.y)) }
+  }
+}
+
+impl Rect<f32> {
+  /// Returns the smallest integer rectangle that contains the rectangle.
+  ///
+  /// Negative coordinates are clamped to zero.
+  pub fn round_out(&self) -> Rect<u32> {
+    Rect {
+      start: Point2::new(
+        self.start.x.floor().max(0.0) as u32,
+        self.start.y.floor().max(0.0) as u32,
+      ),
+      end: Point2::new(self.end.x.ceil().max(0.0) as u32, self.end.y.ceil().max(0.0) as u32),
+    }
+  }
+}
+
+impl<T: ScalarNum> Default for Rect<T> {
+  fn default() -> Self {
+    Rect { start: Point2::origin(), end: Point2::origin() }
+  }
+}
+
+impl From<Rect<u32>> for Rect<f32> {
+  fn from(rect: Rect<u32>) -> Self {
+    Rect {
+      start: Point2::new(rect.start.x as f32, rect.start.y as f32),
+      end: Point2::new(rect.end.x as f32, rect.end.y as f32),
+    }
+  }
+}
+
+impl<T: serde::Serialize + ScalarNum> serde::Serialize for Rect<T> {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    (self.x1(), self.y1(), self.x2(), self.y2()).serialize(serializer)
+  }
+}
+
+impl<'de, T: serde::Deserialize<'de> + ScalarNum> serde::Deserialize<'de> for Rect<T> {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let (x1, y1, x2, y2) = <(T, T, T, T)>::deserialize(deserializer)?;
+
+    Ok(Rect { start: Point2::new(x1, y1), end: Point2::new(x2, y2) })
+  }
+}
+
+impl<T: ScalarNum + fmt::Display> fmt::Display for Rect<T> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "({}, {})..({}, {})", self.x1(), self.y1(), self.x2(), self.y2())
+  }
+}
+
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+  if b < a {
+    b
+  } else {
+    a
+  }
+}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+  if b > a {
+    b
+  } else {
+    a
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Smallest coordinate of the rectangles under test.
+  const MIN: i32 = -2;
+  /// Largest coordinate of the rectangles under test.
+  const MAX: i32 = 2;
+
+  /// Returns every rectangle with coordinates from `MIN` to `MAX` whose end is
+  /// not before its start, including empty ones.
+  fn rects() -> Vec<Rect<i32>> {
+    let mut rects = Vec::new();
+
+    for x1 in MIN..=MAX {
+      for y1 in MIN..=MAX {
+        for x2 in x1..=MAX {
+          for y2 in y1..=MAX {
+            rects.push(Rect::new(Point2::new(x1, y1), Point2::new(x2, y2)));
+          }
+        }
+      }
+    }
+
+    rects
+  }
+
+  /// Returns every point with coordinates from one less than `MIN` to one
+  /// more than `MAX`.
+  fn points() -> Vec<Point2<i32>> {
+    (MIN - 1..=MAX + 1).flat_map(|x| (MIN - 1..=MAX + 1).map(move |y| Point2::new(x, y))).collect()
+  }
+
+  /// Returns `true` if `inner` is entirely within `outer`.
+  fn contains_rect(outer: &Rect<i32>, inner: &Rect<i32>) -> bool {
+    inner.x1() >= outer.x1()
+      && inner.y1() >= outer.y1()
+      && inner.x2() <= outer.x2()
+      && inner.y2() <= outer.y2()
+  }
+
+  #[test]
+  fn accessors_return_edges() {
+    let rect = Rect::new(Point2::new(1, 2), Point2::new(3, 4));
+
+    assert_eq!((rect.x1(), rect.y1(), rect.x2(), rect.y2()), (1, 2, 3, 4));
+  }
+
+  #[test]
+  fn intersection_is_within_both_rects() {
+    let rects = rects();
+
+    for a in &rects {
+      for b in &rects {
+        match a.intersection(b) {
+          Some(rect) => {
+            assert!(!rect.is_empty(), "{} ∩ {} = {} is empty", a, b, rect);
+            assert!(contains_rect(a, &rect), "{} does not contain {} ∩ {}", a, a, b);
+            assert!(contains_rect(b, &rect), "{} does not contain {} ∩ {}", b, a, b);
+          }
+
+          None => assert!(!a.intersects(b), "{} intersects {} with no intersection", a, b),
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn union_contains_both_rects() {
+    let rects = rects();
+
+    for a in &rects {
+      for b in &rects {
+        let union = a.union(b);
+
+        assert!(a.is_empty() || contains_rect(&union, a), "{} ∪ {} misses {}", a, b, a);
+        assert!(b.is_empty() || contains_rect(&union, b), "{} ∪ {} misses {}", a, b, b);
+      }
+    }
+  }
+
+  #[test]
+  fn clamped_points_are_inside_except_on_end_edge() {
+    for rect in rects().iter().filter(|rect| !rect.is_empty()) {
+      for point in points() {
+        let clamped = rect.clamp_point(point);
+
+        if rect.contains_point(point) {
+          assert_eq!(clamped, point);
+        }
+
+        if clamped.x == rect.x2() || clamped.y == rect.y2() {
+          assert!(clamped.x >= rect.x1() && clamped.x <= rect.x2());
+          assert!(clamped.y >= rect.y1() && clamped.y <= rect.y2());
+        } else {
+          assert!(rect.contains_point(clamped), "{} does not contain {}", rect, clamped);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn translate_keeps_size() {
+    for rect in rects() {
+      for point in points() {
+        let moved = rect.translate(point.coords);
+
+        assert_eq!((moved.width(), moved.height()), (rect.width(), rect.height()));
+        assert_eq!(moved.start, rect.start + point.coords);
+      }
+    }
+  }
+
+  #[test]
+  fn inflate_never_has_negative_size() {
+    for rect in rects() {
+      for amount in 0..=3 {
+        let shrunk = rect.inflate(-amount);
+        let grown = rect.inflate(amount);
+
+        assert!(shrunk.width() >= 0 && shrunk.height() >= 0, "{} shrunk to {}", rect, shrunk);
+        assert_eq!(grown.width(), rect.width() + amount * 2);
+        assert_eq!(grown.height(), rect.height() + amount * 2);
+      }
+    }
+  }
+
+  #[test]
+  fn inflate_shrinks_float_rects_to_their_center() {
+    let rect = Rect::new(Point2::new(0.0, 0.0), Point2::new(4.0, 2.0));
+
+    assert_eq!(rect.inflate(0.5), Rect::new(Point2::new(-0.5, -0.5), Point2::new(4.5, 2.5)));
+    assert_eq!(rect.inflate(-1.5), Rect::new(Point2::new(1.5, 1.5), Point2::new(2.5, 1.5)));
+  }
 }
@@ -19,6 +19,18 @@ impl<T: ScalarNum> Size<T> {
   pub fn new(width: T, height: T) -> Self {
     Size { width, height }
   }
+
+  /// Returns the area of the size, its width multiplied by its height.
+  pub fn area(&self) -> T {
+    self.width * self.height
+  }
+}
+
+impl<T: ScalarNum + PartialOrd> Size<T> {
+  /// Returns `true` if the size has no area.
+  pub fn is_empty(&self) -> bool {
+    self.width <= T::zero() || self.height <= T::zero()
+  }
 }
 
 impl<T: ScalarNum> Default for Size<T> {
@@ -47,6 +59,36 @@ impl<T: ScalarNum> ops::Mul<T> for Size<T> {
   }
 }
 
+impl<T: ScalarNum> ops::Div<T> for Size<T> {
+  type Output = Self;
+
+  fn div(self, divisor: T) -> Self {
+    Size { width: self.width / divisor, height: self.height / divisor }
+  }
+}
+
+impl<T: ScalarNum> ops::Add for Size<T> {
+  type Output = Self;
+
+  fn add(self, other: Self) -> Self {
+    Size { width: self.width + other.width, height: self.height + other.height }
+  }
+}
+
+impl<T: ScalarNum> ops::Sub for Size<T> {
+  type Output = Self;
+
+  fn sub(self, other: Self) -> Self {
+    Size { width: self.width - other.width, height: self.height - other.height }
+  }
+}
+
+impl From<Size<u32>> for Size<f32> {
+  fn from(size: Size<u32>) -> Self {
+    Size::new(size.width as f32, size.height as f32)
+  }
+}
+
 impl<T: serde::Serialize + ScalarNum> serde::Serialize for Size<T> {
   fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
     (self.width, self.height).serialize(serializer)
@@ -0,0 +1,443 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use crate::ecs::storage::DenseVecStorage;
+use crate::ecs::{Entity, GlobalTransform, Join as _};
+use std::collections::{HashMap, HashSet};
+
+/// Default width and height of each cell in a `SpatialGrid`, in world units.
+pub const DEFAULT_SPATIAL_GRID_CELL_SIZE: f32 = 64.0;
+
+/// A uniform grid that indexes entities by their bounds for fast proximity
+/// queries.
+///
+/// Each entity is stored in every cell its bounds overlap. Query results are
+/// sorted by entity ID so that they are the same on every run.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+  cell_size: f32,
+  cells: HashMap<(i32, i32), Vec<Entity>>,
+  bounds: HashMap<Entity, Rect<f32>>,
+}
+
+impl SpatialGrid {
+  /// Creates a new, empty grid with the given cell size in world units.
+  pub fn new(cell_size: f32) -> Self {
+    assert!(cell_size > 0.0, "spatial grid cell size must be positive");
+
+    SpatialGrid { cell_size, cells: HashMap::new(), bounds: HashMap::new() }
+  }
+
+  /// Returns the width and height of each cell in world units.
+  pub fn cell_size(&self) -> f32 {
+    self.cell_size
+  }
+
+  /// Returns the number of entities in the grid.
+  pub fn len(&self) -> usize {
+    self.bounds.len()
+  }
+
+  /// Returns `true` if the grid contains no entities.
+  pub fn is_empty(&self) -> bool {
+    self.bounds.is_empty()
+  }
+
+  /// Returns `true` if the grid contains the given entity.
+  pub fn contains(&self, entity: Entity) -> bool {
+    self.bounds.contains_key(&entity)
+  }
+
+  /// Returns the bounds of the given entity, or `None` if it is not in the
+  /// grid.
+  pub fn bounds(&self, entity: Entity) -> Option<Rect<f32>> {
+    self.bounds.get(&entity).cloned()
+  }
+
+  /// Returns an iterator over every entity in the grid and its bounds.
+  pub fn iter(&self) -> impl Iterator<Item = (Entity, Rect<f32>)> + '_ {
+    self.bounds.iter().map(|(entity, bounds)| (*entity, *bounds))
+  }
+
+  /// Adds an entity to the grid with the given bounds, or moves it if it is
+  /// already in the grid.
+  pub fn insert(&mut self, entity: Entity, bounds: Rect<f32>) {
+    if let Some(old) = self.bounds.insert(entity, bounds) {
+      if self.cell_range(&old) == self.cell_range(&bounds) {
+        return;
+      }
+
+      self.remove_from_cells(entity, &old);
+    }
+
+    let (start, end) = self.cell_range(&bounds);
+
+    for y in start.1..=end.1 {
+      for x in start.0..=end.0 {
+        self.cells.entry((x, y)).or_default().push(entity);
+      }
+    }
+  }
+
+  /// Moves an entity already in the grid to the given bounds.
+  ///
+  /// Returns `false` and does nothing if the entity is not in the grid.
+  pub fn move_to(&mut self, entity: Entity, bounds: Rect<f32>) -> bool {
+    if !self.contains(entity) {
+      return false;
+    }
+
+    self.insert(entity, bounds);
+
+    true
+  }
+
+  /// Removes an entity from the grid, returning its bounds if it was in the
+  /// grid.
+  pub fn remove(&mut self, entity: Entity) -> Option<Rect<f32>> {
+    let bounds = self.bounds.remove(&entity)?;
+
+    self.remove_from_cells(entity, &bounds);
+
+    Some(bounds)
+  }
+
+  /// Removes all entities from the grid.
+  pub fn clear(&mut self) {
+    self.cells.clear();
+    self.bounds.clear();
+  }
+
+  /// Returns the entities whose bounds intersect the given region.
+  pub fn query_rect(&self, region: &Rect<f32>) -> Vec<Entity> {
+    let mut results = Vec::new();
+    let (start, end) = self.cell_range(region);
+
+    for y in start.1..=end.1 {
+      for x in start.0..=end.0 {
+        for &entity in self.cells.get(&(x, y)).into_iter().flatten() {
+          if self.bounds[&entity].intersects(region) {
+            results.push(entity);
+          }
+        }
+      }
+    }
+
+    sort_and_dedup(&mut results);
+
+    results
+  }
+
+  /// Returns the entities whose bounds contain the given point.
+  pub fn query_point(&self, point: Point2<f32>) -> Vec<Entity> {
+    let mut results: Vec<Entity> = self
+      .cells
+      .get(&self.cell_at(point))
+      .into_iter()
+      .flatten()
+      .cloned()
+      .filter(|entity| self.bounds[entity].contains_point(point))
+      .collect();
+
+    sort_and_dedup(&mut results);
+
+    results
+  }
+
+  /// Returns up to `count` entities whose bounds are closest to the given
+  /// point, nearest first.
+  ///
+  /// Entities whose bounds contain the point have a distance of zero. Ties are
+  /// broken by entity ID.
+  pub fn nearest(&self, point: Point2<f32>, count: usize) -> Vec<Entity> {
+    if count == 0 || self.is_empty() {
+      return Vec::new();
+    }
+
+    let center = self.cell_at(point);
+    let max_radius = self.max_cell_distance(center);
+    let mut candidates: Vec<(f32, Entity)> = Vec::new();
+    let mut seen = HashSet::new();
+
+    // Search rings of cells outward from the point. Every cell outside ring
+    // `r` is at least `r` cells away, so once enough candidates are closer
+    // than that, no farther cell can contain a closer entity.
+    for radius in 0..=max_radius {
+      // Entities spanning several cells are only counted once.
+      self.for_each_in_ring(center, radius, |entity| {
+        if seen.insert(entity) {
+          candidates.push((distance_to_rect(point, &self.bounds[&entity]), entity));
+        }
+      });
+
+      if candidates.len() >= count {
+        sort_candidates(&mut candidates);
+
+        if candidates[count - 1].0 <= radius as f32 * self.cell_size {
+          break;
+        }
+      }
+    }
+
+    sort_candidates(&mut candidates);
+
+    candidates.into_iter().map(|(_, entity)| entity).take(count).collect()
+  }
+
+  /// Returns the coordinates of the cell containing the given point.
+  fn cell_at(&self, point: Point2<f32>) -> (i32, i32) {
+    ((point.x / self.cell_size).floor() as i32, (point.y / self.cell_size).floor() as i32)
+  }
+
+  /// Returns the coordinates of the first and last cells overlapped by the
+  /// given bounds.
+  fn cell_range(&self, bounds: &Rect<f32>) -> ((i32, i32), (i32, i32)) {
+    let start = self.cell_at(bounds.start);
+    let end = self.cell_at(bounds.end);
+
+    (start, (end.0.max(start.0), end.1.max(start.1)))
+  }
+
+  /// Removes an entity from each cell overlapped by the given bounds.
+  fn remove_from_cells(&mut self, entity: Entity, bounds: &Rect<f32>) {
+    let (start, end) = self.cell_range(bounds);
+
+    for y in start.1..=end.1 {
+      for x in start.0..=end.0 {
+        let is_empty = match self.cells.get_mut(&(x, y)) {
+          Some(cell) => {
+            cell.retain(|e| *e != entity);
+            cell.is_empty()
+          }
+
+          None => continue,
+        };
+
+        if is_empty {
+          self.cells.remove(&(x, y));
+        }
+      }
+    }
+  }
+
+  /// Returns the greatest distance in cells from the given cell to any
+  /// occupied cell.
+  fn max_cell_distance(&self, center: (i32, i32)) -> i32 {
+    self
+      .cells
+      .keys()
+      .map(|&(x, y)| cmp::max((x - center.0).abs(), (y - center.1).abs()))
+      .max()
+      .unwrap_or(0)
+  }
+
+  /// Calls `func` for each entity in the cells exactly `radius` cells away
+  /// from the given cell.
+  fn for_each_in_ring(&self, center: (i32, i32), radius: i32, mut func: impl FnMut(Entity)) {
+    let mut visit = |x: i32, y: i32| {
+      for &entity in self.cells.get(&(x, y)).into_iter().flatten() {
+        func(entity);
+      }
+    };
+
+    if radius == 0 {
+      visit(center.0, center.1);
+
+      return;
+    }
+
+    for x in center.0 - radius..=center.0 + radius {
+      visit(x, center.1 - radius);
+      visit(x, center.1 + radius);
+    }
+
+    for y in center.1 - radius + 1..center.1 + radius {
+      visit(center.0 - radius, y);
+      visit(center.0 + radius, y);
+    }
+  }
+}
+
+impl Default for SpatialGrid {
+  fn default() -> Self {
+    Self::new(DEFAULT_SPATIAL_GRID_CELL_SIZE)
+  }
+}
+
+/// Component containing the bounds of an entity relative to the position of
+/// its `GlobalTransform`.
+///
+/// Entities with both components are kept in the `SpatialGrid` resource by the
+/// `UpdateSpatialGrid` system.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds(pub Rect<f32>);
+
+impl ecs::Component for Bounds {
+  type Storage = DenseVecStorage<Self>;
+}
+
+/// System that keeps the `SpatialGrid` resource in sync with the position and
+/// `Bounds` of every entity.
+///
+/// This system requires the `SpatialGrid` resource and should run after
+/// `ecs::UpdateHierarchy` so that positions are up to date.
+#[derive(Debug, Default)]
+pub struct UpdateSpatialGrid {
+  stale: Vec<Entity>,
+}
+
+impl<'a> ecs::System<'a> for UpdateSpatialGrid {
+  type SystemData = (
+    ecs::Entities<'a>,
+    ecs::Components<'a, GlobalTransform>,
+    ecs::Components<'a, Bounds>,
+    ecs::ResourceMut<'a, SpatialGrid>,
+  );
+
+  fn run(&mut self, (entities, transforms, bounds, mut grid): Self::SystemData) {
+    // Remove entities that were deleted or lost either component.
+    self.stale.extend(grid.iter().map(|(entity, _)| entity).filter(|entity| {
+      !entities.is_alive(*entity)
+        || transforms.get(*entity).is_none()
+        || bounds.get(*entity).is_none()
+    }));
+
+    for entity in self.stale.drain(..) {
+      grid.remove(entity);
+    }
+
+    // Insert or move every other entity.
+    for (entity, transform, bounds) in (&entities, &transforms, &bounds).join() {
+      let rect = bounds.0.translate(transform.position().coords);
+
+      if grid.bounds(entity) != Some(rect) {
+        grid.insert(entity, rect);
+      }
+    }
+  }
+}
+
+/// Returns the distance from a point to the nearest point in a rectangle.
+fn distance_to_rect(point: Point2<f32>, rect: &Rect<f32>) -> f32 {
+  let dx = (rect.start.x - point.x).max(point.x - rect.end.x).max(0.0);
+  let dy = (rect.start.y - point.y).max(point.y - rect.end.y).max(0.0);
+
+  (dx * dx + dy * dy).sqrt()
+}
+
+/// Sorts entities by ID and removes duplicates.
+fn sort_and_dedup(entities: &mut Vec<Entity>) {
+  entities.sort_by_key(|entity| entity.id());
+  entities.dedup();
+}
+
+/// Sorts candidates by distance, then entity ID.
+fn sort_candidates(candidates: &mut [(f32, Entity)]) {
+  candidates.sort_by(|a, b| {
+    a.0.partial_cmp(&b.0).unwrap_or(cmp::Ordering::Equal).then(a.1.id().cmp(&b.1.id()))
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ecs::{Context, LocalTransform, UpdateHierarchy};
+
+  fn rect(x1: f32, y1: f32, x2: f32, y2: f32) -> Rect<f32> {
+    Rect::new(Point2::new(x1, y1), Point2::new(x2, y2))
+  }
+
+  #[test]
+  fn queries_return_sorted_unique_entities() {
+    let ecs = Context::new();
+    let a = ecs.entities().create();
+    let b = ecs.entities().create();
+    let c = ecs.entities().create();
+    let mut grid = SpatialGrid::new(10.0);
+
+    grid.insert(b, rect(5.0, 5.0, 25.0, 25.0));
+    grid.insert(a, rect(0.0, 0.0, 8.0, 8.0));
+    grid.insert(c, rect(40.0, 40.0, 45.0, 45.0));
+
+    assert_eq!(grid.query_rect(&rect(0.0, 0.0, 30.0, 30.0)), vec![a, b]);
+    assert_eq!(grid.query_rect(&rect(20.0, 20.0, 50.0, 50.0)), vec![b, c]);
+    assert_eq!(grid.query_point(Point2::new(6.0, 6.0)), vec![a, b]);
+    assert_eq!(grid.query_point(Point2::new(30.0, 30.0)), vec![]);
+
+    grid.insert(b, rect(60.0, 60.0, 70.0, 70.0));
+
+    assert_eq!(grid.query_rect(&rect(0.0, 0.0, 30.0, 30.0)), vec![a]);
+    assert_eq!(grid.remove(a), Some(rect(0.0, 0.0, 8.0, 8.0)));
+    assert_eq!(grid.query_point(Point2::new(6.0, 6.0)), vec![]);
+    assert_eq!(grid.len(), 2);
+  }
+
+  #[test]
+  fn nearest_orders_by_distance_then_id() {
+    let ecs = Context::new();
+    let a = ecs.entities().create();
+    let b = ecs.entities().create();
+    let c = ecs.entities().create();
+    let mut grid = SpatialGrid::new(10.0);
+
+    grid.insert(c, rect(0.0, 0.0, 2.0, 2.0));
+    grid.insert(b, rect(30.0, 0.0, 32.0, 2.0));
+    grid.insert(a, rect(-30.0, 0.0, -28.0, 2.0));
+
+    assert_eq!(grid.nearest(Point2::new(1.0, 1.0), 1), vec![c]);
+    assert_eq!(grid.nearest(Point2::new(1.0, 1.0), 3), vec![c, a, b]);
+    assert_eq!(grid.nearest(Point2::new(1.0, 1.0), 10), vec![c, a, b]);
+    assert_eq!(grid.nearest(Point2::new(1.0, 1.0), 0), vec![]);
+  }
+
+  #[test]
+  fn nearest_counts_entities_spanning_cells_once() {
+    let ecs = Context::new();
+    let entity = ecs.entities().create();
+    let mut grid = SpatialGrid::new(10.0);
+
+    // Overlaps cells (1, 0) and (1, 1) but not the cell of the query point.
+    grid.insert(entity, rect(12.0, 5.0, 18.0, 15.0));
+
+    assert_eq!(grid.nearest(Point2::new(5.0, 5.0), 2), vec![entity]);
+  }
+
+  #[test]
+  fn update_spatial_grid_tracks_bounds_and_positions() {
+    let mut ecs = Context::new();
+
+    ecs.register_component::<Bounds>();
+    ecs.put_resource(SpatialGrid::new(10.0));
+
+    let a = ecs.entities().create();
+    let b = ecs.entities().create();
+
+    for &(entity, x) in &[(a, 0.0), (b, 20.0)] {
+      ecs
+        .components_mut::<LocalTransform>()
+        .insert(entity, LocalTransform::new(Vector2::new(x, 0.0)));
+      ecs.components_mut::<Bounds>().insert(entity, Bounds(rect(-1.0, -1.0, 1.0, 1.0)));
+    }
+
+    ecs.commit_entities();
+    ecs.run_system(&mut UpdateHierarchy::default());
+    ecs.run_system(&mut UpdateSpatialGrid::default());
+
+    assert_eq!(ecs.resource::<SpatialGrid>().bounds(a), Some(rect(-1.0, -1.0, 1.0, 1.0)));
+    assert_eq!(ecs.resource::<SpatialGrid>().bounds(b), Some(rect(19.0, -1.0, 21.0, 1.0)));
+
+    ecs.components_mut::<LocalTransform>().insert(a, LocalTransform::new(Vector2::new(50.0, 0.0)));
+    ecs.components_mut::<Bounds>().remove(b);
+    ecs.run_system(&mut UpdateHierarchy::default());
+    ecs.run_system(&mut UpdateSpatialGrid::default());
+
+    let grid = ecs.resource::<SpatialGrid>();
+
+    assert_eq!(grid.len(), 1);
+    assert_eq!(grid.bounds(a), Some(rect(49.0, -1.0, 51.0, 1.0)));
+    assert_eq!(grid.query_point(Point2::new(50.0, 0.0)), vec![a]);
+    assert_eq!(grid.query_point(Point2::new(0.0, 0.0)), vec![]);
+  }
+}
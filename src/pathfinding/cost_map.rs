@@ -0,0 +1,85 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+
+/// A grid of tiles with a cost for moving onto each one.
+///
+/// Impassable tiles have no cost. Passable tiles have a cost of at least `1`,
+/// which is multiplied by the length of each step onto the tile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostMap {
+  width: u32,
+  height: u32,
+  costs: Vec<Option<u32>>,
+}
+
+impl CostMap {
+  /// Creates a new cost map of the given size where every tile is passable
+  /// with a cost of `1`.
+  pub fn new(width: u32, height: u32) -> Self {
+    CostMap { width, height, costs: vec![Some(1); width as usize * height as usize] }
+  }
+
+  /// Creates a new cost map from the solid tiles of a `physics::TileLayer`.
+  ///
+  /// Solid tiles are impassable and every other tile has a cost of `1`.
+  pub fn from_tile_layer(layer: &physics::TileLayer) -> Self {
+    let size = layer.size();
+    let mut map = CostMap::new(size.width, size.height);
+
+    for y in 0..size.height {
+      for x in 0..size.width {
+        if layer.is_solid(x, y) {
+          map.set_cost((x, y), None);
+        }
+      }
+    }
+
+    map
+  }
+
+  /// Returns the number of tiles in each dimension.
+  pub fn size(&self) -> Size<u32> {
+    Size::new(self.width, self.height)
+  }
+
+  /// Returns `true` if the given tile is within the map.
+  pub fn contains(&self, tile: Tile) -> bool {
+    tile.0 < self.width && tile.1 < self.height
+  }
+
+  /// Returns the cost of moving onto the given tile, or `None` if it is
+  /// impassable or outside of the map.
+  pub fn cost(&self, tile: Tile) -> Option<u32> {
+    if self.contains(tile) {
+      self.costs[self.index(tile)]
+    } else {
+      None
+    }
+  }
+
+  /// Returns `true` if the given tile is passable.
+  pub fn is_passable(&self, tile: Tile) -> bool {
+    self.cost(tile).is_some()
+  }
+
+  /// Sets the cost of moving onto the given tile, or makes it impassable if
+  /// the cost is `None`.
+  ///
+  /// Costs of `0` are raised to `1` so that the search heuristic stays
+  /// accurate. Panics if the tile is outside of the map.
+  pub fn set_cost(&mut self, tile: Tile, cost: Option<u32>) {
+    assert!(self.contains(tile), "tile coordinates out of range");
+
+    let index = self.index(tile);
+
+    self.costs[index] = cost.map(|cost| cost.max(1));
+  }
+
+  /// Returns the index of the given tile in `costs`.
+  pub(super) fn index(&self, tile: Tile) -> usize {
+    tile.1 as usize * self.width as usize + tile.0 as usize
+  }
+}
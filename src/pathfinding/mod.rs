@@ -0,0 +1,23 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The `pathfinding` module finds paths through a grid of tiles with A*.
+//!
+//! Paths can be found immediately with `find_path`, or requested through the
+//! `PathRequests` resource and found a little at a time each frame by the
+//! `ProcessPathRequests` system, which reports results as `PathEvent` values.
+
+mod cost_map;
+mod options;
+mod requests;
+mod search;
+mod smooth;
+
+pub use self::{cost_map::*, options::*, requests::*, search::*};
+
+use super::*;
+use crate::math::{Point2, Vector2};
+
+/// Coordinates of a tile in a `CostMap`.
+pub type Tile = (u32, u32);
@@ -0,0 +1,66 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+/// Options for finding a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathOptions {
+  /// Directions a path can move in.
+  pub movement: Movement,
+  /// When a diagonal step can pass by impassable tiles.
+  pub corner_cutting: CornerCutting,
+  /// Whether to remove waypoints that can be skipped by moving in a straight
+  /// line. With `Movement::FourWay`, only horizontal and vertical lines are
+  /// used.
+  pub smooth: bool,
+  /// Maximum number of tiles to explore before giving up, or `None` for no
+  /// limit.
+  pub max_nodes: Option<usize>,
+}
+
+impl Default for PathOptions {
+  fn default() -> Self {
+    PathOptions {
+      movement: Movement::EightWay,
+      corner_cutting: CornerCutting::Never,
+      smooth: false,
+      max_nodes: None,
+    }
+  }
+}
+
+/// Directions a path can move in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement {
+  /// Horizontal and vertical steps only.
+  FourWay,
+  /// Horizontal, vertical, and diagonal steps.
+  EightWay,
+}
+
+/// When a diagonal step can pass by impassable tiles.
+///
+/// A diagonal step passes by the two tiles that share an edge with both the
+/// tile it starts on and the tile it ends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CornerCutting {
+  /// Diagonal steps are only allowed if both tiles they pass by are passable.
+  Never,
+  /// Diagonal steps are allowed if at least one tile they pass by is
+  /// passable.
+  IfOneOpen,
+  /// Diagonal steps are always allowed.
+  Always,
+}
+
+impl CornerCutting {
+  /// Returns `true` if a diagonal step is allowed given whether each of the
+  /// tiles it passes by is passable.
+  pub fn allows(self, first_open: bool, second_open: bool) -> bool {
+    match self {
+      CornerCutting::Never => first_open && second_open,
+      CornerCutting::IfOneOpen => first_open || second_open,
+      CornerCutting::Always => true,
+    }
+  }
+}
@@ -0,0 +1,162 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use std::collections::VecDeque;
+
+/// Default number of tiles `ProcessPathRequests` explores each frame.
+pub const DEFAULT_NODES_PER_FRAME: usize = 1024;
+
+/// Identifies a request made with `PathRequests::request`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PathRequestId(u64);
+
+/// Resource containing path requests waiting to be processed by the
+/// `ProcessPathRequests` system.
+///
+/// Requests are processed in the order they were made.
+#[derive(Debug, Default)]
+pub struct PathRequests {
+  next_id: u64,
+  pending: VecDeque<PendingRequest>,
+}
+
+#[derive(Debug)]
+struct PendingRequest {
+  id: PathRequestId,
+  entity: ecs::Entity,
+  search: PathSearch,
+}
+
+impl PathRequests {
+  /// Creates a new, empty set of requests.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Requests a path for an entity from the start tile to the goal tile.
+  ///
+  /// The result is written as a `PathEvent` with the returned ID once the
+  /// search finishes.
+  pub fn request(
+    &mut self,
+    entity: ecs::Entity,
+    start: Tile,
+    goal: Tile,
+    options: PathOptions,
+  ) -> PathRequestId {
+    let id = PathRequestId(self.next_id);
+
+    self.next_id += 1;
+    self.pending.push_back(PendingRequest {
+      id,
+      entity,
+      search: PathSearch::new(start, goal, options),
+    });
+
+    id
+  }
+
+  /// Cancels a request so that no event is written for it.
+  ///
+  /// Returns `false` if the request already finished or was canceled.
+  pub fn cancel(&mut self, id: PathRequestId) -> bool {
+    let len = self.pending.len();
+
+    self.pending.retain(|request| request.id != id);
+    self.pending.len() != len
+  }
+
+  /// Cancels all requests for the given entity.
+  pub fn cancel_entity(&mut self, entity: ecs::Entity) {
+    self.pending.retain(|request| request.entity != entity);
+  }
+
+  /// Returns the number of requests waiting to finish.
+  pub fn len(&self) -> usize {
+    self.pending.len()
+  }
+
+  /// Returns `true` if no requests are waiting to finish.
+  pub fn is_empty(&self) -> bool {
+    self.pending.is_empty()
+  }
+}
+
+/// An event emitted by `ProcessPathRequests` when a path request finishes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathEvent {
+  /// A path was found.
+  Found { id: PathRequestId, entity: ecs::Entity, path: Path },
+  /// No path could be found.
+  Failed { id: PathRequestId, entity: ecs::Entity, error: PathError },
+}
+
+/// System that searches for the paths in `PathRequests`, exploring a limited
+/// number of tiles each frame so that long searches cannot stall a frame.
+///
+/// This system requires the `CostMap`, `PathRequests`, and
+/// `ecs::EventChannel<PathEvent>` resources.
+#[derive(Debug)]
+pub struct ProcessPathRequests {
+  nodes_per_frame: usize,
+}
+
+impl ProcessPathRequests {
+  /// Creates a new system that explores `DEFAULT_NODES_PER_FRAME` tiles each
+  /// frame.
+  pub fn new() -> Self {
+    Self::with_nodes_per_frame(DEFAULT_NODES_PER_FRAME)
+  }
+
+  /// Creates a new system that explores the given number of tiles each frame.
+  pub fn with_nodes_per_frame(nodes_per_frame: usize) -> Self {
+    Self { nodes_per_frame }
+  }
+}
+
+impl Default for ProcessPathRequests {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<'a> ecs::System<'a> for ProcessPathRequests {
+  type SystemData = (
+    ecs::Resource<'a, CostMap>,
+    ecs::ResourceMut<'a, PathRequests>,
+    ecs::ResourceMut<'a, ecs::EventChannel<PathEvent>>,
+  );
+
+  fn run(&mut self, (map, mut requests, mut events): Self::SystemData) {
+    let mut budget = self.nodes_per_frame;
+
+    while budget > 0 {
+      let request = match requests.pending.front_mut() {
+        Some(request) => request,
+        None => break,
+      };
+
+      let expanded = request.search.expanded();
+      let status = request.search.step(&map, budget);
+
+      // Always use up at least one node so the loop ends even when searches
+      // finish without exploring anything.
+      budget = budget.saturating_sub(cmp::max(request.search.expanded() - expanded, 1));
+
+      let result = match status {
+        SearchStatus::InProgress => continue,
+        SearchStatus::Finished(result) => result,
+      };
+
+      let PendingRequest { id, entity, .. } =
+        requests.pending.pop_front().expect("finished request was not pending");
+
+      events.single_write(match result {
+        Ok(path) => PathEvent::Found { id, entity, path },
+        Err(error) => PathEvent::Failed { id, entity, error },
+      });
+    }
+  }
+}
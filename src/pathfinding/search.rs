@@ -0,0 +1,365 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::smooth::smooth;
+use super::*;
+use std::collections::{BinaryHeap, HashMap};
+use std::f32::consts::SQRT_2;
+
+/// Orthogonal neighbor offsets in the order they are explored.
+const ORTHOGONAL: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Diagonal neighbor offsets in the order they are explored.
+const DIAGONAL: [(i64, i64); 4] = [(1, -1), (1, 1), (-1, 1), (-1, -1)];
+
+/// A path found through a `CostMap`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+  /// Tiles along the path from the start tile to the goal tile.
+  ///
+  /// If the path was smoothed, consecutive tiles may be far apart and should
+  /// be moved between in a straight line.
+  pub tiles: Vec<Tile>,
+  /// Total cost of the path before smoothing.
+  pub cost: f32,
+}
+
+impl Path {
+  /// Returns the world position of the center of each tile on the path, given
+  /// the position of the map's top-left corner and the size of each tile.
+  pub fn waypoints(&self, origin: Point2<f32>, tile_size: f32) -> Vec<Point2<f32>> {
+    self
+      .tiles
+      .iter()
+      .map(|&(x, y)| {
+        origin + Vector2::new((x as f32 + 0.5) * tile_size, (y as f32 + 0.5) * tile_size)
+      })
+      .collect()
+  }
+}
+
+/// An error that occurred while finding a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathError {
+  /// The start or goal tile is impassable or outside of the map.
+  Blocked,
+  /// No path connects the start and goal tiles.
+  NoPath,
+  /// The search explored `PathOptions::max_nodes` tiles without reaching the
+  /// goal.
+  BudgetExceeded,
+}
+
+impl fmt::Display for PathError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      PathError::Blocked => write!(f, "start or goal tile is impassable"),
+      PathError::NoPath => write!(f, "no path to goal"),
+      PathError::BudgetExceeded => write!(f, "search budget exceeded"),
+    }
+  }
+}
+
+/// The state of a `PathSearch` after a step.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchStatus {
+  /// The search has not finished yet.
+  InProgress,
+  /// The search finished with the given result.
+  Finished(Result<Path, PathError>),
+}
+
+/// An A* search that can be run a few tiles at a time.
+///
+/// Ties between equally promising tiles are broken in a fixed order, so the
+/// same search on the same map always finds the same path.
+#[derive(Debug, Clone)]
+pub struct PathSearch {
+  start: Tile,
+  goal: Tile,
+  options: PathOptions,
+  state: Option<SearchState>,
+  expanded: usize,
+}
+
+#[derive(Debug, Clone)]
+struct SearchState {
+  open: BinaryHeap<OpenNode>,
+  costs: HashMap<Tile, f32>,
+  came_from: HashMap<Tile, Tile>,
+  next_sequence: u64,
+}
+
+/// A tile waiting to be explored, ordered so that the best tile is the
+/// greatest.
+#[derive(Debug, Clone, Copy)]
+struct OpenNode {
+  tile: Tile,
+  estimate: f32,
+  heuristic: f32,
+  sequence: u64,
+}
+
+impl PathSearch {
+  /// Creates a new search from the start tile to the goal tile.
+  pub fn new(start: Tile, goal: Tile, options: PathOptions) -> Self {
+    PathSearch { start, goal, options, state: None, expanded: 0 }
+  }
+
+  /// Returns the start tile of the search.
+  pub fn start(&self) -> Tile {
+    self.start
+  }
+
+  /// Returns the goal tile of the search.
+  pub fn goal(&self) -> Tile {
+    self.goal
+  }
+
+  /// Returns the number of tiles explored so far.
+  pub fn expanded(&self) -> usize {
+    self.expanded
+  }
+
+  /// Explores up to `max_nodes` more tiles of the map.
+  ///
+  /// The map should not change between steps of the same search.
+  pub fn step(&mut self, map: &CostMap, max_nodes: usize) -> SearchStatus {
+    if self.state.is_none() {
+      if !map.is_passable(self.start) || !map.is_passable(self.goal) {
+        return SearchStatus::Finished(Err(PathError::Blocked));
+      }
+
+      let mut state = SearchState {
+        open: BinaryHeap::new(),
+        costs: HashMap::new(),
+        came_from: HashMap::new(),
+        next_sequence: 0,
+      };
+
+      state.costs.insert(self.start, 0.0);
+      state.push(self.start, 0.0, self.heuristic(self.start));
+
+      self.state = Some(state);
+    }
+
+    for _ in 0..max_nodes {
+      let state = self.state.as_mut().expect("search state was not initialized");
+
+      let node = match state.open.pop() {
+        Some(node) => node,
+        None => return SearchStatus::Finished(Err(PathError::NoPath)),
+      };
+
+      let cost = state.costs[&node.tile];
+
+      // Skip outdated entries for tiles that were since reached more cheaply.
+      if node.estimate > cost + node.heuristic {
+        continue;
+      }
+
+      if node.tile == self.goal {
+        return SearchStatus::Finished(Ok(self.finish(map)));
+      }
+
+      if let Some(limit) = self.options.max_nodes {
+        if self.expanded >= limit {
+          return SearchStatus::Finished(Err(PathError::BudgetExceeded));
+        }
+      }
+
+      self.expanded += 1;
+
+      for (neighbor, distance) in self.neighbors(map, node.tile) {
+        let step_cost = map.cost(neighbor).unwrap_or(1) as f32 * distance;
+        let new_cost = cost + step_cost;
+        let heuristic = self.heuristic(neighbor);
+        let state = self.state.as_mut().expect("search state was not initialized");
+
+        if state.costs.get(&neighbor).map(|&c| new_cost < c).unwrap_or(true) {
+          state.costs.insert(neighbor, new_cost);
+          state.came_from.insert(neighbor, node.tile);
+          state.push(neighbor, new_cost, heuristic);
+        }
+      }
+    }
+
+    SearchStatus::InProgress
+  }
+
+  /// Builds the path to the goal after it has been reached.
+  fn finish(&self, map: &CostMap) -> Path {
+    let state = self.state.as_ref().expect("search state was not initialized");
+    let mut tiles = vec![self.goal];
+    let mut tile = self.goal;
+
+    while let Some(&previous) = state.came_from.get(&tile) {
+      tiles.push(previous);
+      tile = previous;
+    }
+
+    tiles.reverse();
+
+    if self.options.smooth {
+      tiles = smooth(map, &tiles, self.options.movement, self.options.corner_cutting);
+    }
+
+    Path { tiles, cost: state.costs[&self.goal] }
+  }
+
+  /// Returns the passable neighbors of a tile and the length of the step to
+  /// each one.
+  fn neighbors(&self, map: &CostMap, tile: Tile) -> Vec<(Tile, f32)> {
+    let mut neighbors = Vec::with_capacity(8);
+
+    for &(dx, dy) in &ORTHOGONAL {
+      if let Some(neighbor) = offset(map, tile, dx, dy) {
+        neighbors.push((neighbor, 1.0));
+      }
+    }
+
+    if self.options.movement == Movement::EightWay {
+      for &(dx, dy) in &DIAGONAL {
+        let neighbor = match offset(map, tile, dx, dy) {
+          Some(neighbor) => neighbor,
+          None => continue,
+        };
+
+        let first_open = offset(map, tile, dx, 0).is_some();
+        let second_open = offset(map, tile, 0, dy).is_some();
+
+        if self.options.corner_cutting.allows(first_open, second_open) {
+          neighbors.push((neighbor, SQRT_2));
+        }
+      }
+    }
+
+    neighbors
+  }
+
+  /// Returns the estimated cost from a tile to the goal, which never
+  /// overestimates because every tile costs at least `1`.
+  fn heuristic(&self, tile: Tile) -> f32 {
+    let dx = (tile.0 as f32 - self.goal.0 as f32).abs();
+    let dy = (tile.1 as f32 - self.goal.1 as f32).abs();
+
+    match self.options.movement {
+      Movement::FourWay => dx + dy,
+      Movement::EightWay => dx.max(dy) + (SQRT_2 - 1.0) * dx.min(dy),
+    }
+  }
+}
+
+impl SearchState {
+  /// Adds a tile to the open set.
+  fn push(&mut self, tile: Tile, cost: f32, heuristic: f32) {
+    self.open.push(OpenNode {
+      tile,
+      estimate: cost + heuristic,
+      heuristic,
+      sequence: self.next_sequence,
+    });
+
+    self.next_sequence += 1;
+  }
+}
+
+impl PartialEq for OpenNode {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == cmp::Ordering::Equal
+  }
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+  fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for OpenNode {
+  fn cmp(&self, other: &Self) -> cmp::Ordering {
+    // Prefer the lowest estimate, then the tile closest to the goal, then the
+    // tile added first.
+    other
+      .estimate
+      .partial_cmp(&self.estimate)
+      .unwrap_or(cmp::Ordering::Equal)
+      .then_with(|| other.heuristic.partial_cmp(&self.heuristic).unwrap_or(cmp::Ordering::Equal))
+      .then_with(|| other.sequence.cmp(&self.sequence))
+  }
+}
+
+/// Finds a path from the start tile to the goal tile.
+///
+/// This searches the whole map at once unless `options.max_nodes` is set. Use
+/// `PathRequests` to spread long searches over multiple frames instead.
+pub fn find_path(
+  map: &CostMap,
+  start: Tile,
+  goal: Tile,
+  options: PathOptions,
+) -> Result<Path, PathError> {
+  let mut search = PathSearch::new(start, goal, options);
+
+  loop {
+    if let SearchStatus::Finished(result) = search.step(map, usize::max_value()) {
+      return result;
+    }
+  }
+}
+
+/// Returns the tile at the given offset from another tile if it is passable.
+fn offset(map: &CostMap, tile: Tile, dx: i64, dy: i64) -> Option<Tile> {
+  let x = tile.0 as i64 + dx;
+  let y = tile.1 as i64 + dy;
+
+  if x < 0 || y < 0 {
+    return None;
+  }
+
+  let neighbor = (x as u32, y as u32);
+
+  if map.is_passable(neighbor) {
+    Some(neighbor)
+  } else {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn four_way_smoothed() -> PathOptions {
+    PathOptions { movement: Movement::FourWay, smooth: true, ..PathOptions::default() }
+  }
+
+  #[test]
+  fn four_way_smoothing_keeps_segments_axis_aligned() {
+    let map = CostMap::new(4, 4);
+    let path = find_path(&map, (0, 0), (3, 3), four_way_smoothed()).expect("no path found");
+
+    assert_eq!(path.tiles.first(), Some(&(0, 0)));
+    assert_eq!(path.tiles.last(), Some(&(3, 3)));
+
+    for segment in path.tiles.windows(2) {
+      assert!(
+        segment[0].0 == segment[1].0 || segment[0].1 == segment[1].1,
+        "diagonal segment from {:?} to {:?}",
+        segment[0],
+        segment[1]
+      );
+    }
+  }
+
+  #[test]
+  fn four_way_smoothing_joins_straight_runs() {
+    let map = CostMap::new(4, 1);
+    let path = find_path(&map, (0, 0), (3, 0), four_way_smoothed()).expect("no path found");
+
+    assert_eq!(path.tiles, vec![(0, 0), (3, 0)]);
+  }
+}
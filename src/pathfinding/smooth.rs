@@ -0,0 +1,103 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+
+/// Removes waypoints from a path that can be skipped by moving in a straight
+/// line between tile centers.
+///
+/// A waypoint is only skipped if the straight line passes through passable
+/// tiles that cost no more than the most expensive tile on the part of the
+/// path it replaces, so smoothing never crosses a wall or wades through
+/// costlier terrain. Where the line passes exactly through a tile corner, the
+/// two tiles beside the corner are checked according to `corner_cutting`.
+///
+/// With `Movement::FourWay`, only waypoints in the same row or column are
+/// joined, so the smoothed path never moves diagonally.
+pub(super) fn smooth(
+  map: &CostMap,
+  tiles: &[Tile],
+  movement: Movement,
+  corner_cutting: CornerCutting,
+) -> Vec<Tile> {
+  if tiles.len() <= 2 {
+    return tiles.to_vec();
+  }
+
+  let mut smoothed = vec![tiles[0]];
+  let mut anchor = 0;
+  let mut max_cost = 0;
+
+  for index in 1..tiles.len() {
+    max_cost = max_cost.max(map.cost(tiles[index]).unwrap_or(0));
+
+    if index == anchor + 1 {
+      continue;
+    }
+
+    let is_aligned = movement == Movement::EightWay
+      || tiles[anchor].0 == tiles[index].0
+      || tiles[anchor].1 == tiles[index].1;
+
+    if !is_aligned || !is_line_clear(map, tiles[anchor], tiles[index], max_cost, corner_cutting) {
+      // Keep the last tile that could be reached directly and continue from
+      // there.
+      anchor = index - 1;
+      max_cost = map.cost(tiles[index]).unwrap_or(0);
+      smoothed.push(tiles[anchor]);
+    }
+  }
+
+  smoothed.push(tiles[tiles.len() - 1]);
+  smoothed
+}
+
+/// Returns `true` if every tile a straight line between the centers of two
+/// tiles passes through is passable with a cost of at most `max_cost`.
+fn is_line_clear(
+  map: &CostMap,
+  from: Tile,
+  to: Tile,
+  max_cost: u32,
+  corner_cutting: CornerCutting,
+) -> bool {
+  let is_clear = |x: i64, y: i64| -> bool {
+    x >= 0 && y >= 0 && map.cost((x as u32, y as u32)).map(|c| c <= max_cost).unwrap_or(false)
+  };
+
+  let (mut x, mut y) = (from.0 as i64, from.1 as i64);
+  let (dx, dy) = (to.0 as i64 - x, to.1 as i64 - y);
+  let (nx, ny) = (dx.abs(), dy.abs());
+  let (sx, sy) = (dx.signum(), dy.signum());
+  let (mut ix, mut iy) = (0, 0);
+
+  // Walk every tile the line touches, deciding at each step whether the line
+  // next crosses a vertical edge, a horizontal edge, or a corner.
+  while ix < nx || iy < ny {
+    let decision = (1 + 2 * ix) * ny - (1 + 2 * iy) * nx;
+
+    if decision == 0 {
+      if !corner_cutting.allows(is_clear(x + sx, y), is_clear(x, y + sy)) {
+        return false;
+      }
+
+      x += sx;
+      y += sy;
+      ix += 1;
+      iy += 1;
+    } else if decision < 0 {
+      x += sx;
+      ix += 1;
+    } else {
+      y += sy;
+      iy += 1;
+    }
+
+    if !is_clear(x, y) {
+      return false;
+    }
+  }
+
+  true
+}
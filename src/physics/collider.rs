@@ -0,0 +1,106 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use crate::ecs::storage::DenseVecStorage;
+
+/// Collision layer bits that match every layer.
+pub const ALL_LAYERS: u32 = !0;
+
+/// Collision layer bits of new colliders and tile layers.
+pub const DEFAULT_LAYER: u32 = 1;
+
+/// Component that gives an entity a collision shape.
+///
+/// A collider belongs to the collision layers in `layer` and collides with
+/// colliders or tile layers that belong to any of the layers in `mask`.
+/// Trigger colliders never block movement; instead they report entities that
+/// enter or exit them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Collider {
+  /// Shape of the collider.
+  pub shape: Shape,
+  /// Offset of the center of the shape from the entity's position.
+  pub offset: Vector2<f32>,
+  /// Layer bits the collider belongs to.
+  pub layer: u32,
+  /// Layer bits the collider collides with.
+  pub mask: u32,
+  /// Whether the collider is a trigger instead of a solid.
+  pub is_trigger: bool,
+}
+
+impl Collider {
+  /// Creates a new solid collider with the given shape on the default layer
+  /// that collides with all layers.
+  pub fn new(shape: Shape) -> Self {
+    Collider {
+      shape,
+      offset: Vector2::zeros(),
+      layer: DEFAULT_LAYER,
+      mask: ALL_LAYERS,
+      is_trigger: false,
+    }
+  }
+
+  /// Creates a new trigger collider with the given shape on the default layer
+  /// that detects all layers.
+  pub fn trigger(shape: Shape) -> Self {
+    Collider { is_trigger: true, ..Collider::new(shape) }
+  }
+
+  /// Returns a copy of the collider with the given offset.
+  pub fn with_offset(mut self, offset: Vector2<f32>) -> Self {
+    self.offset = offset;
+    self
+  }
+
+  /// Returns a copy of the collider on the given layers.
+  pub fn with_layer(mut self, layer: u32) -> Self {
+    self.layer = layer;
+    self
+  }
+
+  /// Returns a copy of the collider that collides with the given layers.
+  pub fn with_mask(mut self, mask: u32) -> Self {
+    self.mask = mask;
+    self
+  }
+
+  /// Returns `true` if the collider collides with anything on the given
+  /// layers.
+  pub fn collides_with(&self, layer: u32) -> bool {
+    self.mask & layer != 0
+  }
+
+  /// Returns the center of the collider's shape for an entity at the given
+  /// position.
+  pub fn center(&self, position: Vector2<f32>) -> Point2<f32> {
+    Point2::from(position + self.offset)
+  }
+
+  /// Returns the bounding rectangle of the collider for an entity at the
+  /// given position.
+  pub fn bounds(&self, position: Vector2<f32>) -> Rect<f32> {
+    self.shape.bounds_at(self.center(position))
+  }
+}
+
+impl ecs::Component for Collider {
+  type Storage = DenseVecStorage<Self>;
+}
+
+/// Component containing the velocity of an entity in world units per second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Velocity(pub Vector2<f32>);
+
+impl Default for Velocity {
+  fn default() -> Self {
+    Velocity(Vector2::zeros())
+  }
+}
+
+impl ecs::Component for Velocity {
+  type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,27 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+
+/// What a moving collider ran into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionTarget {
+  /// The collider of another entity.
+  Entity(ecs::Entity),
+  /// A solid tile in a `TileLayer`, by layer index and tile coordinates.
+  Tile { layer: usize, x: u32, y: u32 },
+}
+
+/// An event emitted by `MoveColliders`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PhysicsEvent {
+  /// A moving entity was stopped by a solid collider or tile.
+  ///
+  /// The `normal` points away from the target along the blocked axis.
+  Collision { entity: ecs::Entity, target: CollisionTarget, normal: Vector2<f32> },
+  /// An entity started overlapping a trigger collider.
+  TriggerEntered { trigger: ecs::Entity, entity: ecs::Entity },
+  /// An entity stopped overlapping a trigger collider.
+  TriggerExited { trigger: ecs::Entity, entity: ecs::Entity },
+}
@@ -0,0 +1,22 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The `physics` module provides lightweight collision detection and response
+//! for top-down 2D games.
+//!
+//! Entities with a `Collider`, a `Velocity`, and an `ecs::LocalTransform` are
+//! moved by the `MoveColliders` system, which slides them along any solid
+//! colliders or `TileLayer` tiles in their way. Collisions and trigger overlaps
+//! are reported as `PhysicsEvent` values.
+
+mod collider;
+mod events;
+mod movement;
+mod shape;
+mod tiles;
+
+pub use self::{collider::*, events::*, movement::*, shape::*, tiles::*};
+
+use super::*;
+use crate::math::{clamp, Point2, Vector2};
@@ -0,0 +1,507 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::shape::blocked_interval;
+use super::*;
+use crate::ecs::{Join as _, LocalTransform};
+use crate::math::SpatialGrid;
+use crate::time::GameClocks;
+use std::collections::BTreeSet;
+
+/// Distance within which a collider is considered to be touching an obstacle,
+/// to absorb floating point error.
+const CONTACT_EPSILON: f32 = 1.0e-4;
+
+/// System that moves entities by their `Velocity`, stopping them at solid
+/// colliders and tiles, and reports collisions and trigger overlaps.
+///
+/// Each entity moves along the x axis and then the y axis, so an entity
+/// moving diagonally into a wall slides along it. Entities are moved one at
+/// a time in order of entity ID, and each one is blocked by the others at
+/// their current positions, so results are deterministic.
+///
+/// Positions are read from and written to `ecs::LocalTransform`, so colliders
+/// should be on entities without a `Parent`.
+///
+/// This system must run in `Phase::FixedUpdate`, because it moves entities by
+/// the fixed delta of its game clock each time it runs. It requires the
+/// `GameClocks`, `TileCollision`, and `ecs::EventChannel<PhysicsEvent>`
+/// resources.
+#[derive(Debug)]
+pub struct MoveColliders {
+  clock: String,
+  grid: SpatialGrid,
+  overlaps: BTreeSet<(ecs::Entity, ecs::Entity)>,
+}
+
+/// A solid obstacle found during a sweep.
+struct Obstacle {
+  target: CollisionTarget,
+  shape: Shape,
+  center: Point2<f32>,
+}
+
+impl MoveColliders {
+  /// Creates a new system that uses the `GameClocks::WORLD` clock.
+  pub fn new() -> Self {
+    Self::with_clock(GameClocks::WORLD)
+  }
+
+  /// Creates a new system that uses the game clock with the given name.
+  pub fn with_clock(name: &str) -> Self {
+    Self { clock: name.into(), grid: SpatialGrid::default(), overlaps: BTreeSet::new() }
+  }
+
+  /// Collects the solid obstacles in the given area that a collider collides
+  /// with, in a deterministic order.
+  fn obstacles(
+    &self,
+    entity: ecs::Entity,
+    collider: &Collider,
+    area: &Rect<f32>,
+    colliders: &ecs::Components<Collider>,
+    transforms: &ecs::ComponentsMut<LocalTransform>,
+    tiles: &TileCollision,
+  ) -> Vec<Obstacle> {
+    let mut obstacles = Vec::new();
+
+    for other in self.grid.query_rect(area) {
+      if other == entity {
+        continue;
+      }
+
+      let (other_collider, transform) = match (colliders.get(other), transforms.get(other)) {
+        (Some(c), Some(t)) => (c, t),
+        _ => continue,
+      };
+
+      if other_collider.is_trigger || !collider.collides_with(other_collider.layer) {
+        continue;
+      }
+
+      obstacles.push(Obstacle {
+        target: CollisionTarget::Entity(other),
+        shape: other_collider.shape,
+        center: other_collider.center(transform.position),
+      });
+    }
+
+    for (index, layer) in tiles.layers().iter().enumerate() {
+      if !collider.collides_with(layer.layer) {
+        continue;
+      }
+
+      let half = layer.tile_size() / 2.0;
+
+      for (x, y) in layer.solid_tiles_in(area) {
+        obstacles.push(Obstacle {
+          target: CollisionTarget::Tile { layer: index, x, y },
+          shape: Shape::Box { half_extents: Vector2::new(half, half) },
+          center: layer.tile_rect(x, y).center(),
+        });
+      }
+    }
+
+    obstacles
+  }
+}
+
+impl Default for MoveColliders {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<'a> ecs::System<'a> for MoveColliders {
+  type SystemData = (
+    ecs::Entities<'a>,
+    ecs::Resource<'a, GameClocks>,
+    ecs::Resource<'a, TileCollision>,
+    ecs::ResourceMut<'a, ecs::EventChannel<PhysicsEvent>>,
+    ecs::Components<'a, Collider>,
+    ecs::Components<'a, Velocity>,
+    ecs::ComponentsMut<'a, LocalTransform>,
+  );
+
+  fn run(
+    &mut self,
+    (entities, clocks, tiles, mut events, colliders, velocities, mut transforms): Self::SystemData,
+  ) {
+    let delta = match clocks.get(&self.clock) {
+      Some(clock) => clock.fixed_delta().as_secs() as f32,
+      None => return,
+    };
+
+    // Index every collider for the broad phase.
+    self.grid.clear();
+
+    for (entity, collider) in (&entities, &colliders).join() {
+      if let Some(transform) = transforms.get(entity) {
+        self.grid.insert(entity, collider.bounds(transform.position));
+      }
+    }
+
+    // Move each entity one axis at a time.
+    let movers: Vec<_> = (&entities, &colliders, &velocities)
+      .join()
+      .filter(|(_, _, velocity)| velocity.0 != Vector2::zeros())
+      .map(|(entity, collider, velocity)| (entity, *collider, velocity.0 * delta))
+      .collect();
+
+    for (entity, collider, movement) in movers {
+      let mut position = match transforms.get(entity) {
+        Some(transform) => transform.position,
+        None => continue,
+      };
+
+      for axis in 0..2 {
+        if movement[axis] == 0.0 {
+          continue;
+        }
+
+        if collider.is_trigger {
+          position[axis] += movement[axis];
+          continue;
+        }
+
+        let center = collider.center(position);
+        let mut target = center;
+
+        target[axis] += movement[axis];
+
+        let area = collider.shape.bounds_at(center).union(&collider.shape.bounds_at(target));
+        let obstacles = self.obstacles(entity, &collider, &area, &colliders, &transforms, &tiles);
+
+        let (allowed, hit) = sweep(&collider.shape, center, movement[axis], axis, &obstacles);
+
+        position[axis] += allowed;
+
+        if let Some(target) = hit {
+          let mut normal = Vector2::zeros();
+
+          normal[axis] = -movement[axis].signum();
+
+          events.single_write(PhysicsEvent::Collision { entity, target, normal });
+        }
+      }
+
+      if let Some(transform) = transforms.get_mut(entity) {
+        transform.position = position;
+      }
+
+      self.grid.insert(entity, collider.bounds(position));
+    }
+
+    // Find everything overlapping each trigger and report the changes.
+    let mut overlaps = BTreeSet::new();
+
+    for (trigger, collider) in (&entities, &colliders).join() {
+      let center = match transforms.get(trigger) {
+        Some(transform) if collider.is_trigger => collider.center(transform.position),
+        _ => continue,
+      };
+
+      for other in self.grid.query_rect(&collider.shape.bounds_at(center)) {
+        if other == trigger {
+          continue;
+        }
+
+        let (other_collider, transform) = match (colliders.get(other), transforms.get(other)) {
+          (Some(c), Some(t)) => (c, t),
+          _ => continue,
+        };
+
+        let other_center = other_collider.center(transform.position);
+
+        if collider.collides_with(other_collider.layer)
+          && collider.shape.overlaps(center, &other_collider.shape, other_center)
+        {
+          overlaps.insert((trigger, other));
+        }
+      }
+    }
+
+    for &(trigger, entity) in overlaps.difference(&self.overlaps) {
+      events.single_write(PhysicsEvent::TriggerEntered { trigger, entity });
+    }
+
+    for &(trigger, entity) in self.overlaps.difference(&overlaps) {
+      events.single_write(PhysicsEvent::TriggerExited { trigger, entity });
+    }
+
+    self.overlaps = overlaps;
+  }
+}
+
+/// Moves a shape from `center` by `movement` along `axis` until it touches the
+/// first obstacle in the way.
+///
+/// Returns the distance the shape can move and the obstacle it hit, if any.
+/// Obstacles the shape already overlaps are ignored so that it can move out of
+/// them.
+fn sweep(
+  shape: &Shape,
+  center: Point2<f32>,
+  movement: f32,
+  axis: usize,
+  obstacles: &[Obstacle],
+) -> (f32, Option<CollisionTarget>) {
+  let start = center[axis];
+  let across = center[1 - axis];
+  let mut end = start + movement;
+  let mut hit = None;
+
+  for obstacle in obstacles {
+    let (min, max) = match blocked_interval(shape, across, &obstacle.shape, obstacle.center, axis) {
+      Some(interval) => interval,
+      None => continue,
+    };
+
+    if movement > 0.0 {
+      if min >= start - CONTACT_EPSILON && min < end {
+        end = min.max(start);
+        hit = Some(obstacle.target);
+      }
+    } else if max <= start + CONTACT_EPSILON && max > end {
+      end = max.min(start);
+      hit = Some(obstacle.target);
+    }
+  }
+
+  (end - start, hit)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::time::seconds;
+
+  /// Returns a context with the resources and components `MoveColliders`
+  /// needs and a fixed interval of a quarter second.
+  fn context() -> ecs::Context {
+    let mut ecs = ecs::Context::new();
+    let mut clocks = GameClocks::new();
+
+    clocks.insert(GameClocks::WORLD).set_fixed_interval(seconds(0.25));
+
+    ecs.register_component::<Collider>();
+    ecs.register_component::<Velocity>();
+    ecs.put_resource(clocks);
+    ecs.put_resource(TileCollision::new());
+    ecs.put_resource(ecs::EventChannel::<PhysicsEvent>::new());
+    ecs
+  }
+
+  /// Creates an entity with a collider at the given position, moving at the
+  /// given velocity in world units per second.
+  fn spawn(
+    ecs: &mut ecs::Context,
+    position: Vector2<f32>,
+    collider: Collider,
+    velocity: Vector2<f32>,
+  ) -> ecs::Entity {
+    let entity = ecs.entities().create();
+
+    ecs.components_mut::<LocalTransform>().insert(entity, LocalTransform::new(position));
+    ecs.components_mut::<Collider>().insert(entity, collider);
+    ecs.components_mut::<Velocity>().insert(entity, Velocity(velocity));
+    ecs.commit_entities();
+
+    entity
+  }
+
+  /// Returns a tile layer of four unit tiles in a row with the third tile
+  /// solid.
+  fn tile_wall() -> TileLayer {
+    let mut layer = TileLayer::new(1.0, 4, 1);
+
+    layer.set_solid(2, 0, true);
+    layer
+  }
+
+  fn position(ecs: &ecs::Context, entity: ecs::Entity) -> Vector2<f32> {
+    ecs.components::<LocalTransform>().get(entity).expect("entity has no transform").position
+  }
+
+  fn read_events(
+    ecs: &ecs::Context,
+    reader: &mut ecs::EventReaderId<PhysicsEvent>,
+  ) -> Vec<PhysicsEvent> {
+    ecs.resource::<ecs::EventChannel<PhysicsEvent>>().read(reader).cloned().collect()
+  }
+
+  #[test]
+  fn moves_by_fixed_delta() {
+    let mut ecs = context();
+    let mover = spawn(
+      &mut ecs,
+      Vector2::zeros(),
+      Collider::new(Shape::rect(1.0, 1.0)),
+      Vector2::new(4.0, 2.0),
+    );
+
+    // The frame delta must not affect how far an entity moves.
+    ecs.resource_mut::<GameClocks>().insert(GameClocks::WORLD).advance(seconds(1.0));
+    ecs.run_system(&mut MoveColliders::new());
+
+    assert_eq!(position(&ecs, mover), Vector2::new(1.0, 0.5));
+  }
+
+  #[test]
+  fn slides_along_walls() {
+    let mut ecs = context();
+    let mut reader = ecs.resource::<ecs::EventChannel<PhysicsEvent>>().register_reader();
+
+    let wall = spawn(
+      &mut ecs,
+      Vector2::new(0.0, -1.0),
+      Collider::new(Shape::rect(10.0, 1.0)),
+      Vector2::zeros(),
+    );
+
+    let mover = spawn(
+      &mut ecs,
+      Vector2::zeros(),
+      Collider::new(Shape::rect(1.0, 1.0)),
+      Vector2::new(4.0, -4.0),
+    );
+
+    ecs.run_system(&mut MoveColliders::new());
+
+    assert_eq!(position(&ecs, mover), Vector2::new(1.0, 0.0));
+    assert_eq!(position(&ecs, wall), Vector2::new(0.0, -1.0));
+
+    assert_eq!(
+      read_events(&ecs, &mut reader),
+      vec![PhysicsEvent::Collision {
+        entity: mover,
+        target: CollisionTarget::Entity(wall),
+        normal: Vector2::new(0.0, 1.0),
+      }]
+    );
+  }
+
+  #[test]
+  fn stops_at_solid_tiles() {
+    let mut ecs = context();
+    let mut reader = ecs.resource::<ecs::EventChannel<PhysicsEvent>>().register_reader();
+
+    ecs.resource_mut::<TileCollision>().add_layer(tile_wall());
+
+    let mut system = MoveColliders::new();
+
+    let mover = spawn(
+      &mut ecs,
+      Vector2::new(0.5, 0.5),
+      Collider::new(Shape::rect(1.0, 1.0)),
+      Vector2::new(8.0, 0.0),
+    );
+
+    ecs.run_system(&mut system);
+
+    assert_eq!(position(&ecs, mover), Vector2::new(1.5, 0.5));
+
+    assert_eq!(
+      read_events(&ecs, &mut reader),
+      vec![PhysicsEvent::Collision {
+        entity: mover,
+        target: CollisionTarget::Tile { layer: 0, x: 2, y: 0 },
+        normal: Vector2::new(-1.0, 0.0),
+      }]
+    );
+
+    // Moving again while touching the tile stays put.
+    ecs.run_system(&mut system);
+
+    assert_eq!(position(&ecs, mover), Vector2::new(1.5, 0.5));
+  }
+
+  #[test]
+  fn ignores_layers_outside_of_mask() {
+    let mut ecs = context();
+    let mut reader = ecs.resource::<ecs::EventChannel<PhysicsEvent>>().register_reader();
+
+    let mut tiles = tile_wall();
+
+    tiles.layer = 2;
+
+    ecs.resource_mut::<TileCollision>().add_layer(tiles);
+
+    spawn(
+      &mut ecs,
+      Vector2::new(2.5, 2.5),
+      Collider::new(Shape::rect(1.0, 1.0)).with_layer(4),
+      Vector2::zeros(),
+    );
+
+    let blocked = spawn(
+      &mut ecs,
+      Vector2::new(0.5, 0.5),
+      Collider::new(Shape::rect(1.0, 1.0)).with_mask(2),
+      Vector2::new(8.0, 0.0),
+    );
+
+    let ghost = spawn(
+      &mut ecs,
+      Vector2::new(0.5, 2.5),
+      Collider::new(Shape::rect(1.0, 1.0)).with_mask(1),
+      Vector2::new(8.0, 0.0),
+    );
+
+    ecs.run_system(&mut MoveColliders::new());
+
+    // The tiles are on a layer in the mask of `blocked` only, and the solid
+    // entity in the path of `ghost` is on a layer in neither mask.
+    assert_eq!(position(&ecs, blocked), Vector2::new(1.5, 0.5));
+    assert_eq!(position(&ecs, ghost), Vector2::new(2.5, 2.5));
+
+    let events = read_events(&ecs, &mut reader);
+
+    assert_eq!(events.len(), 1);
+
+    match events[0] {
+      PhysicsEvent::Collision { entity, .. } => assert_eq!(entity, blocked),
+      ref event => panic!("unexpected event {:?}", event),
+    }
+  }
+
+  #[test]
+  fn reports_trigger_enter_and_exit() {
+    let mut ecs = context();
+    let mut reader = ecs.resource::<ecs::EventChannel<PhysicsEvent>>().register_reader();
+    let mut system = MoveColliders::new();
+
+    let trigger =
+      spawn(&mut ecs, Vector2::zeros(), Collider::trigger(Shape::rect(2.0, 2.0)), Vector2::zeros());
+
+    let entity = spawn(
+      &mut ecs,
+      Vector2::new(-3.0, 0.0),
+      Collider::new(Shape::rect(1.0, 1.0)),
+      Vector2::new(8.0, 0.0),
+    );
+
+    ecs.run_system(&mut system);
+
+    assert_eq!(position(&ecs, entity), Vector2::new(-1.0, 0.0));
+    assert_eq!(
+      read_events(&ecs, &mut reader),
+      vec![PhysicsEvent::TriggerEntered { trigger, entity }]
+    );
+
+    // Still overlapping, so nothing is reported.
+    ecs.run_system(&mut system);
+
+    assert_eq!(position(&ecs, entity), Vector2::new(1.0, 0.0));
+    assert_eq!(read_events(&ecs, &mut reader), vec![]);
+
+    ecs.run_system(&mut system);
+
+    assert_eq!(position(&ecs, entity), Vector2::new(3.0, 0.0));
+    assert_eq!(
+      read_events(&ecs, &mut reader),
+      vec![PhysicsEvent::TriggerExited { trigger, entity }]
+    );
+  }
+}
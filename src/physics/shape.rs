@@ -0,0 +1,100 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+
+/// The shape of a `Collider`, centered on the collider's position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+  /// An axis-aligned box extending `half_extents` in each direction.
+  Box { half_extents: Vector2<f32> },
+  /// A circle with the given radius.
+  Circle { radius: f32 },
+}
+
+impl Shape {
+  /// Creates a box shape with the given width and height.
+  pub fn rect(width: f32, height: f32) -> Self {
+    Shape::Box { half_extents: Vector2::new(width / 2.0, height / 2.0) }
+  }
+
+  /// Creates a circle shape with the given radius.
+  pub fn circle(radius: f32) -> Self {
+    Shape::Circle { radius }
+  }
+
+  /// Returns the bounding rectangle of the shape centered on the given point.
+  pub fn bounds_at(&self, center: Point2<f32>) -> Rect<f32> {
+    let half = match *self {
+      Shape::Box { half_extents } => half_extents,
+      Shape::Circle { radius } => Vector2::new(radius, radius),
+    };
+
+    Rect::new(center - half, center + half)
+  }
+
+  /// Returns `true` if the shape centered on `center` overlaps `other`
+  /// centered on `other_center`.
+  ///
+  /// Shapes that only touch at their edges do not overlap.
+  pub fn overlaps(&self, center: Point2<f32>, other: &Shape, other_center: Point2<f32>) -> bool {
+    match blocked_interval(self, center[1], other, other_center, 0) {
+      Some((start, end)) => center[0] > start && center[0] < end,
+      None => false,
+    }
+  }
+
+  /// Returns the box half extents and circle radius of the shape, so that
+  /// every shape can be treated as a rounded box.
+  fn rounded_box(&self) -> (Vector2<f32>, f32) {
+    match *self {
+      Shape::Box { half_extents } => (half_extents, 0.0),
+      Shape::Circle { radius } => (Vector2::zeros(), radius),
+    }
+  }
+}
+
+/// Returns the open interval of positions along `axis` where a `mover` shape
+/// overlaps an `obstacle` shape, given the mover's position `across` on the
+/// other axis.
+///
+/// Returns `None` if the shapes cannot overlap at that position on the other
+/// axis.
+pub(crate) fn blocked_interval(
+  mover: &Shape,
+  across: f32,
+  obstacle: &Shape,
+  obstacle_center: Point2<f32>,
+  axis: usize,
+) -> Option<(f32, f32)> {
+  let other_axis = 1 - axis;
+
+  // The set of overlapping mover positions is the Minkowski sum of the two
+  // shapes, which is itself a rounded box.
+  let (mover_half, mover_radius) = mover.rounded_box();
+  let (obstacle_half, obstacle_radius) = obstacle.rounded_box();
+
+  let half = mover_half + obstacle_half;
+  let radius = mover_radius + obstacle_radius;
+
+  let gap = (across - obstacle_center[other_axis]).abs() - half[other_axis];
+
+  let rounding = if radius > 0.0 {
+    let distance = gap.max(0.0);
+
+    if distance >= radius {
+      return None;
+    }
+
+    (radius * radius - distance * distance).sqrt()
+  } else if gap < 0.0 {
+    0.0
+  } else {
+    return None;
+  };
+
+  let extent = half[axis] + rounding;
+
+  Some((obstacle_center[axis] - extent, obstacle_center[axis] + extent))
+}
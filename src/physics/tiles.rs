@@ -0,0 +1,146 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use hibitset::BitSet;
+
+/// A static grid of solid square tiles that colliders cannot move through.
+#[derive(Debug, Clone)]
+pub struct TileLayer {
+  /// World position of the top-left corner of the first tile.
+  pub origin: Point2<f32>,
+  /// Layer bits the tiles belong to.
+  pub layer: u32,
+  tile_size: f32,
+  width: u32,
+  height: u32,
+  solid: BitSet,
+}
+
+impl TileLayer {
+  /// Creates a new tile layer with the given tile size and number of tiles in
+  /// each dimension. All tiles start out empty.
+  pub fn new(tile_size: f32, width: u32, height: u32) -> Self {
+    assert!(tile_size > 0.0, "tile size must be positive");
+
+    TileLayer {
+      origin: Point2::origin(),
+      layer: DEFAULT_LAYER,
+      tile_size,
+      width,
+      height,
+      solid: BitSet::new(),
+    }
+  }
+
+  /// Returns the width and height of each tile in world units.
+  pub fn tile_size(&self) -> f32 {
+    self.tile_size
+  }
+
+  /// Returns the number of tiles in each dimension.
+  pub fn size(&self) -> Size<u32> {
+    Size::new(self.width, self.height)
+  }
+
+  /// Returns `true` if the tile at the given coordinates is solid.
+  ///
+  /// Tiles outside of the layer are never solid.
+  pub fn is_solid(&self, x: u32, y: u32) -> bool {
+    x < self.width && y < self.height && self.solid.contains(self.index(x, y))
+  }
+
+  /// Sets whether the tile at the given coordinates is solid.
+  ///
+  /// Panics if the coordinates are outside of the layer.
+  pub fn set_solid(&mut self, x: u32, y: u32, solid: bool) {
+    assert!(x < self.width && y < self.height, "tile coordinates out of range");
+
+    let index = self.index(x, y);
+
+    if solid {
+      self.solid.add(index);
+    } else {
+      self.solid.remove(index);
+    }
+  }
+
+  /// Returns the world rectangle covered by the tile at the given
+  /// coordinates.
+  pub fn tile_rect(&self, x: u32, y: u32) -> Rect<f32> {
+    let start = self.origin + Vector2::new(x as f32 * self.tile_size, y as f32 * self.tile_size);
+
+    Rect::new(start, start + Vector2::new(self.tile_size, self.tile_size))
+  }
+
+  /// Returns the coordinates of the solid tiles that overlap the given world
+  /// rectangle, in row-major order.
+  pub fn solid_tiles_in(&self, rect: &Rect<f32>) -> Vec<(u32, u32)> {
+    let to_tile = |value: f32, origin: f32, max: u32| -> u32 {
+      let tile = ((value - origin) / self.tile_size).floor();
+
+      clamp(tile, 0.0..=max as f32) as u32
+    };
+
+    if self.width == 0 || self.height == 0 {
+      return Vec::new();
+    }
+
+    let start_x = to_tile(rect.start.x, self.origin.x, self.width - 1);
+    let start_y = to_tile(rect.start.y, self.origin.y, self.height - 1);
+    let end_x = to_tile(rect.end.x, self.origin.x, self.width - 1);
+    let end_y = to_tile(rect.end.y, self.origin.y, self.height - 1);
+
+    let mut tiles = Vec::new();
+
+    for y in start_y..=end_y {
+      for x in start_x..=end_x {
+        if self.is_solid(x, y) && self.tile_rect(x, y).intersects(rect) {
+          tiles.push((x, y));
+        }
+      }
+    }
+
+    tiles
+  }
+
+  /// Returns the index of the tile at the given coordinates.
+  fn index(&self, x: u32, y: u32) -> u32 {
+    y * self.width + x
+  }
+}
+
+/// Resource containing the static tile layers that colliders move through.
+#[derive(Debug, Default, Clone)]
+pub struct TileCollision {
+  layers: Vec<TileLayer>,
+}
+
+impl TileCollision {
+  /// Creates a new resource with no tile layers.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds a tile layer, returning its index.
+  pub fn add_layer(&mut self, layer: TileLayer) -> usize {
+    self.layers.push(layer);
+    self.layers.len() - 1
+  }
+
+  /// Returns a reference to the tile layer with the given index.
+  pub fn layer(&self, index: usize) -> Option<&TileLayer> {
+    self.layers.get(index)
+  }
+
+  /// Returns a mutable reference to the tile layer with the given index.
+  pub fn layer_mut(&mut self, index: usize) -> Option<&mut TileLayer> {
+    self.layers.get_mut(index)
+  }
+
+  /// Returns the tile layers in the order they were added.
+  pub fn layers(&self) -> &[TileLayer] {
+    &self.layers
+  }
+}
@@ -7,6 +7,7 @@ use super::*;
 /// Tracks time across individual calls to a `tick()` function.
 #[derive(Default, Debug)]
 pub struct Clock {
+  source: Option<Arc<dyn Source>>,
   ticked_at: Option<Instant>,
   interval: Duration,
   intervals_synchronized: bool,
@@ -15,14 +16,21 @@ pub struct Clock {
   elapsed_intervals: u64,
   elapsed_intervals_delta: u64,
   elapsed_interval_remainder: Duration,
+  max_intervals_per_tick: Option<u64>,
 }
 
 impl Clock {
-  /// Creates a new clock.
+  /// Creates a new clock which uses the global time source.
   pub fn new() -> Self {
     Self::default()
   }
 
+  /// Creates a new clock which uses the given time source instead of the
+  /// global time source.
+  pub fn with_source(source: Arc<dyn Source>) -> Self {
+    Self { source: Some(source), ..Self::default() }
+  }
+
   /// Returns the instant and time when the clock was last ticked.
   ///
   /// # Panics
@@ -64,7 +72,7 @@ impl Clock {
   /// Returns the duration of time elapsed between the last two ticks, or zero
   /// if the clock has not been ticked twice.
   pub fn elapsed_delta(&self) -> Duration {
-    self.elapsed
+    self.elapsed_delta
   }
 
   /// Returns the total number of intervals elapsed as of the most recent tick.
@@ -75,49 +83,100 @@ impl Clock {
     self.elapsed_intervals
   }
 
-  /// Returns the number of intervals elapsed between tho two most recent ticks.
+  /// Returns the number of intervals elapsed between the two most recent
+  /// ticks.
   ///
   /// This is zero if no intervals elapsed or if the clock did not have a set
-  /// interval on the most recent tick.
+  /// interval on the most recent tick. It is never more than the maximum set
+  /// with `set_max_intervals_per_tick()`.
   pub fn elapsed_intervals_delta(&self) -> u64 {
     self.elapsed_intervals_delta
   }
 
+  /// Returns the fraction of an interval that has elapsed since the last whole
+  /// interval as of the most recent tick, from `0.0` up to but not including
+  /// `1.0`.
+  ///
+  /// This is useful for interpolating between the two most recent states of a
+  /// simulation that updates once per interval. It is zero if no interval is
+  /// set.
+  pub fn interval_progress(&self) -> f64 {
+    if self.interval > Duration::ZERO {
+      self.elapsed_interval_remainder.as_secs() / self.interval.as_secs()
+    } else {
+      0.0
+    }
+  }
+
+  /// Returns the maximum number of intervals counted per tick, or `None` if
+  /// there is no maximum.
+  pub fn max_intervals_per_tick(&self) -> Option<u64> {
+    self.max_intervals_per_tick
+  }
+
+  /// Sets the maximum number of intervals counted per tick.
+  ///
+  /// If more intervals than the maximum elapse between two ticks, such as
+  /// after a long stall, the extra intervals are discarded. This prevents a
+  /// simulation that runs once per interval from falling further and further
+  /// behind when it cannot keep up.
+  pub fn set_max_intervals_per_tick(&mut self, value: Option<u64>) {
+    self.max_intervals_per_tick = value;
+  }
+
   /// Updates the values tracked by the clock based on the current time.
   ///
   /// If the clock is set to synchronize intervals, this method will block until
   /// roughly one interval has elapsed if one has not already. If the interval
   /// is zero, this method will never block and will not track intervals.
   pub fn tick(&mut self) {
+    let source = self.source.clone().unwrap_or_else(source);
+    let mut ticked_at = source.now();
+
+    // If the clock is synchronized, try to wait for the ideal delta time to
+    // elapse, which is the time remaining until another interval elapses.
+    if let Some(prev_ticked_at) = self.ticked_at {
+      let ideal_elapsed_delta = self.interval - self.elapsed_interval_remainder;
+      let elapsed_delta = ticked_at - prev_ticked_at;
+
+      if self.intervals_synchronized && elapsed_delta < ideal_elapsed_delta {
+        source.spin_sleep(ideal_elapsed_delta - elapsed_delta);
+
+        ticked_at = source.now();
+      }
+    }
+
+    self.tick_at(ticked_at);
+  }
+
+  /// Updates the values tracked by the clock as if it were ticked at the given
+  /// instant.
+  ///
+  /// This method never blocks, even if the clock is set to synchronize
+  /// intervals. Instants earlier than the previous tick are treated as if no
+  /// time has elapsed, and do not change `ticked_at()`.
+  pub fn tick_at(&mut self, ticked_at: Instant) {
     // Get the instant of the last tick or return immediately if this is the
     // first tick.
     let prev_ticked_at = match self.ticked_at {
       Some(then) => then,
 
       None => {
-        self.ticked_at = Some(now());
+        self.ticked_at = Some(ticked_at);
+
         return;
       }
     };
 
-    // Compute the ideal elapsed delta, which is the time remaining until
-    // another interval elapses.
-    let ideal_elapsed_delta = self.interval - self.elapsed_interval_remainder;
-
-    // Compute the actual elapsed delta.
-    let mut ticked_at = now();
-    let mut elapsed_delta = ticked_at - prev_ticked_at;
+    // Keep the latest instant so that time before an out-of-order tick is not
+    // counted twice.
+    let mut elapsed_delta = if ticked_at > prev_ticked_at {
+      self.ticked_at = Some(ticked_at);
 
-    // If the clock is synchronized, try to wait for the ideal delta time to
-    // elapse.
-    if self.intervals_synchronized && elapsed_delta < ideal_elapsed_delta {
-      spin_sleep(ideal_elapsed_delta - elapsed_delta);
-
-      ticked_at = now();
-      elapsed_delta = ticked_at - prev_ticked_at;
-    }
-
-    self.ticked_at = Some(ticked_at);
+      ticked_at - prev_ticked_at
+    } else {
+      Duration::ZERO
+    };
 
     self.elapsed_delta = elapsed_delta;
     self.elapsed += elapsed_delta;
@@ -126,13 +185,123 @@ impl Clock {
     if self.interval > Duration::ZERO {
       elapsed_delta += self.elapsed_interval_remainder;
 
-      let elapsed_intervals_delta = (elapsed_delta.as_secs() / self.interval.as_secs()).floor();
+      let mut elapsed_intervals_delta =
+        (elapsed_delta.as_secs() / self.interval.as_secs()).floor() as u64;
+
+      let remainder = elapsed_delta - self.interval * elapsed_intervals_delta as f64;
+
+      // Discard any intervals over the maximum.
+      if let Some(max) = self.max_intervals_per_tick {
+        elapsed_intervals_delta = cmp::min(elapsed_intervals_delta, max);
+      }
 
-      self.elapsed_intervals_delta = elapsed_intervals_delta as u64;
-      self.elapsed_interval_remainder = elapsed_delta - self.interval * elapsed_intervals_delta;
-      self.elapsed_intervals += self.elapsed_intervals_delta;
+      self.elapsed_intervals_delta = elapsed_intervals_delta;
+      self.elapsed_interval_remainder = remainder;
+      self.elapsed_intervals += elapsed_intervals_delta;
     } else {
-      self.elapsed_intervals = 0;
+      self.elapsed_intervals_delta = 0;
+      self.elapsed_interval_remainder = Duration::ZERO;
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Returns a clock with an interval of a quarter second, which is exactly
+  /// representable so that interval math in the tests is exact.
+  fn quarter_second_clock() -> Clock {
+    let mut clock = Clock::with_source(Arc::new(ManualSource::new()));
+
+    clock.set_interval(seconds(0.25));
+    clock
+  }
+
+  #[test]
+  fn elapsed_delta_is_time_between_last_two_ticks() {
+    let mut clock = Clock::new();
+    let start = now();
+
+    clock.tick_at(start);
+
+    assert_eq!(clock.elapsed_delta(), Duration::ZERO);
+
+    clock.tick_at(start + seconds(0.5));
+
+    assert_eq!(clock.elapsed_delta(), seconds(0.5));
+    assert_eq!(clock.elapsed(), seconds(0.5));
+
+    clock.tick_at(start + seconds(0.75));
+
+    assert_eq!(clock.elapsed_delta(), seconds(0.25));
+    assert_eq!(clock.elapsed(), seconds(0.75));
+  }
+
+  #[test]
+  fn intervals_carry_remainder_across_ticks() {
+    let mut clock = quarter_second_clock();
+    let start = now();
+
+    clock.tick_at(start);
+    clock.tick_at(start + seconds(0.375));
+
+    assert_eq!(clock.elapsed_intervals_delta(), 1);
+    assert_eq!(clock.interval_progress(), 0.5);
+
+    // The leftover eighth of a second combines with this tick's delta.
+    clock.tick_at(start + seconds(0.75));
+
+    assert_eq!(clock.elapsed_intervals_delta(), 2);
+    assert_eq!(clock.elapsed_intervals(), 3);
+    assert_eq!(clock.interval_progress(), 0.0);
+
+    clock.tick_at(start + seconds(0.875));
+
+    assert_eq!(clock.elapsed_intervals_delta(), 0);
+    assert_eq!(clock.elapsed_intervals(), 3);
+    assert_eq!(clock.interval_progress(), 0.5);
+  }
+
+  #[test]
+  fn max_intervals_per_tick_discards_extra_intervals() {
+    let mut clock = quarter_second_clock();
+    let start = now();
+
+    clock.set_max_intervals_per_tick(Some(2));
+    clock.tick_at(start);
+    clock.tick_at(start + seconds(1.375));
+
+    assert_eq!(clock.elapsed_intervals_delta(), 2);
+    assert_eq!(clock.elapsed_intervals(), 2);
+    assert_eq!(clock.interval_progress(), 0.5);
+
+    // The discarded intervals are not made up on later ticks.
+    clock.tick_at(start + seconds(1.5));
+
+    assert_eq!(clock.elapsed_intervals_delta(), 1);
+    assert_eq!(clock.elapsed_intervals(), 3);
+  }
+
+  #[test]
+  fn out_of_order_instant_counts_as_no_time() {
+    let mut clock = quarter_second_clock();
+    let start = now();
+
+    clock.tick_at(start);
+    clock.tick_at(start + seconds(1.0));
+    clock.tick_at(start + seconds(0.5));
+
+    assert_eq!(clock.elapsed_delta(), Duration::ZERO);
+    assert_eq!(clock.elapsed_intervals_delta(), 0);
+    assert_eq!(clock.elapsed(), seconds(1.0));
+    assert!(clock.ticked_at() == start + seconds(1.0));
+
+    // Time is measured from the latest instant, not the out-of-order one.
+    clock.tick_at(start + seconds(1.25));
+
+    assert_eq!(clock.elapsed_delta(), seconds(0.25));
+    assert_eq!(clock.elapsed(), seconds(1.25));
+    assert_eq!(clock.elapsed_intervals(), 5);
+  }
+}
@@ -0,0 +1,121 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::f32::consts::PI;
+
+/// An easing curve which maps linear progress to eased progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+  Linear,
+  QuadIn,
+  QuadOut,
+  QuadInOut,
+  CubicIn,
+  CubicOut,
+  CubicInOut,
+  SineIn,
+  SineOut,
+  SineInOut,
+  BackIn,
+  BackOut,
+  BackInOut,
+  ElasticIn,
+  ElasticOut,
+  ElasticInOut,
+  BounceIn,
+  BounceOut,
+  BounceInOut,
+}
+
+impl Easing {
+  /// Applies the easing curve to a progress value from `0.0` to `1.0`.
+  ///
+  /// The result is `0.0` at the start and `1.0` at the end, but the back and
+  /// elastic curves overshoot that range in between.
+  pub fn apply(self, t: f32) -> f32 {
+    let t = t.max(0.0).min(1.0);
+
+    match self {
+      Easing::Linear => t,
+
+      Easing::QuadIn => t * t,
+      Easing::QuadOut => 1.0 - (1.0 - t).powi(2),
+      Easing::QuadInOut => in_out(t, |t| t * t),
+
+      Easing::CubicIn => t.powi(3),
+      Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+      Easing::CubicInOut => in_out(t, |t| t.powi(3)),
+
+      Easing::SineIn => 1.0 - (t * PI / 2.0).cos(),
+      Easing::SineOut => (t * PI / 2.0).sin(),
+      Easing::SineInOut => -((t * PI).cos() - 1.0) / 2.0,
+
+      Easing::BackIn => back_in(t),
+      Easing::BackOut => 1.0 - back_in(1.0 - t),
+      Easing::BackInOut => in_out(t, back_in),
+
+      Easing::ElasticIn => elastic_in(t),
+      Easing::ElasticOut => 1.0 - elastic_in(1.0 - t),
+      Easing::ElasticInOut => in_out(t, elastic_in),
+
+      Easing::BounceIn => 1.0 - bounce_out(1.0 - t),
+      Easing::BounceOut => bounce_out(t),
+      Easing::BounceInOut => in_out(t, |t| 1.0 - bounce_out(1.0 - t)),
+    }
+  }
+}
+
+impl Default for Easing {
+  fn default() -> Self {
+    Easing::Linear
+  }
+}
+
+/// Builds an in-out curve from an in curve by applying it to the first half
+/// and its mirror image to the second half.
+fn in_out(t: f32, ease_in: impl Fn(f32) -> f32) -> f32 {
+  if t < 0.5 {
+    ease_in(t * 2.0) / 2.0
+  } else {
+    1.0 - ease_in((1.0 - t) * 2.0) / 2.0
+  }
+}
+
+/// An in curve that pulls back slightly before moving forward.
+fn back_in(t: f32) -> f32 {
+  const OVERSHOOT: f32 = 1.701_58;
+
+  (OVERSHOOT + 1.0) * t.powi(3) - OVERSHOOT * t * t
+}
+
+/// An in curve that oscillates with increasing amplitude.
+fn elastic_in(t: f32) -> f32 {
+  if t <= 0.0 || t >= 1.0 {
+    return t;
+  }
+
+  -(2.0f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin()
+}
+
+/// An out curve that bounces to a stop.
+fn bounce_out(t: f32) -> f32 {
+  const N: f32 = 7.5625;
+  const D: f32 = 2.75;
+
+  if t < 1.0 / D {
+    N * t * t
+  } else if t < 2.0 / D {
+    let t = t - 1.5 / D;
+
+    N * t * t + 0.75
+  } else if t < 2.5 / D {
+    let t = t - 2.25 / D;
+
+    N * t * t + 0.9375
+  } else {
+    let t = t - 2.625 / D;
+
+    N * t * t + 0.984_375
+  }
+}
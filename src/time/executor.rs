@@ -0,0 +1,171 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use futures::task::{Context, Poll};
+use futures::Future;
+use std::cell::Cell;
+use std::pin::Pin;
+
+thread_local! {
+  static CURRENT_FRAME: Cell<Option<Frame>> = Cell::new(None);
+}
+
+/// The frame an `Executor` is polling tasks for.
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+  /// The number of frames the executor has run, including this one.
+  number: u64,
+  /// The elapsed game time as of this frame.
+  elapsed: Duration,
+}
+
+/// Runs tasks once per frame against game time.
+///
+/// Tasks are futures that can wait for game time to pass with `delay()` or for
+/// the next frame with `next_frame()`, so scripted sequences can be written as
+/// plain `async` blocks:
+///
+/// ```ignore
+/// executor.spawn(async move {
+///   show_dialog("...");
+///   time::delay(time::seconds(2.0)).await;
+///   hide_dialog();
+/// });
+/// ```
+///
+/// Every unfinished task is polled once each time `run_frame()` is called, so
+/// tasks do not need to be woken.
+#[derive(Default)]
+pub struct Executor {
+  tasks: Vec<Pin<Box<dyn Future<Output = ()>>>>,
+  frames: u64,
+}
+
+impl Executor {
+  /// Creates a new executor with no tasks.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds a task to the executor, which is first polled on the next call to
+  /// `run_frame()`.
+  pub fn spawn(&mut self, task: impl Future<Output = ()> + 'static) {
+    self.tasks.push(Box::pin(task));
+  }
+
+  /// Returns the number of unfinished tasks.
+  pub fn len(&self) -> usize {
+    self.tasks.len()
+  }
+
+  /// Returns `true` if there are no unfinished tasks.
+  pub fn is_empty(&self) -> bool {
+    self.tasks.is_empty()
+  }
+
+  /// Polls every unfinished task once, using `elapsed` as the current game
+  /// time, and removes any tasks that finish.
+  pub fn run_frame(&mut self, elapsed: Duration) {
+    self.frames += 1;
+
+    let frame = Frame { number: self.frames, elapsed };
+    let previous = CURRENT_FRAME.with(|current| current.replace(Some(frame)));
+
+    let waker = futures::task::noop_waker_ref();
+    let mut context = Context::from_waker(waker);
+
+    let mut i = 0;
+
+    while i < self.tasks.len() {
+      match self.tasks[i].as_mut().poll(&mut context) {
+        Poll::Ready(()) => {
+          drop(self.tasks.swap_remove(i));
+        }
+
+        Poll::Pending => i += 1,
+      }
+    }
+
+    CURRENT_FRAME.with(|current| current.set(previous));
+  }
+}
+
+impl fmt::Debug for Executor {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("Executor")
+      .field("tasks", &self.tasks.len())
+      .field("frames", &self.frames)
+      .finish()
+  }
+}
+
+/// Returns a future that resolves once the given duration of game time has
+/// elapsed.
+///
+/// The delay starts when the future is first polled. It must be polled by an
+/// `Executor`.
+pub fn delay(duration: Duration) -> Delay {
+  Delay { duration, deadline: None }
+}
+
+/// Returns a future that resolves on the next frame.
+///
+/// It must be polled by an `Executor`.
+pub fn next_frame() -> NextFrame {
+  NextFrame { started_on: None }
+}
+
+/// A future returned from `delay()`.
+#[derive(Debug)]
+pub struct Delay {
+  duration: Duration,
+  deadline: Option<Duration>,
+}
+
+impl Future for Delay {
+  type Output = ();
+
+  fn poll(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<()> {
+    let frame = current_frame();
+    let duration = self.duration;
+    let deadline = *self.deadline.get_or_insert(frame.elapsed + duration);
+
+    if frame.elapsed >= deadline {
+      Poll::Ready(())
+    } else {
+      Poll::Pending
+    }
+  }
+}
+
+/// A future returned from `next_frame()`.
+#[derive(Debug)]
+pub struct NextFrame {
+  started_on: Option<u64>,
+}
+
+impl Future for NextFrame {
+  type Output = ();
+
+  fn poll(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<()> {
+    let frame = current_frame();
+    let started_on = *self.started_on.get_or_insert(frame.number);
+
+    if frame.number > started_on {
+      Poll::Ready(())
+    } else {
+      Poll::Pending
+    }
+  }
+}
+
+/// Returns the frame the current thread's `Executor` is running.
+///
+/// # Panics
+///
+/// This function panics if no executor is running a frame on this thread.
+fn current_frame() -> Frame {
+  CURRENT_FRAME.with(Cell::get).expect("time futures must be polled by a time::Executor")
+}
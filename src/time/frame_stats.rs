@@ -0,0 +1,234 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use std::collections::{BTreeMap, VecDeque};
+
+/// The number of frames a `FrameStats` created with `new()` keeps.
+pub const DEFAULT_FRAME_STATS_WINDOW: usize = 600;
+
+/// A resource containing timing statistics for a rolling window of recent
+/// frames.
+#[derive(Debug)]
+pub struct FrameStats {
+  window: usize,
+  frame_times: VecDeque<Duration>,
+  phase_times: BTreeMap<String, VecDeque<Duration>>,
+  summary_interval: Option<Duration>,
+  since_summary: Duration,
+}
+
+impl FrameStats {
+  /// Creates a new, empty set of statistics with the default window size and
+  /// a summary interval of ten seconds.
+  pub fn new() -> Self {
+    Self::with_window(DEFAULT_FRAME_STATS_WINDOW)
+  }
+
+  /// Creates a new, empty set of statistics which keeps timings for the given
+  /// number of recent frames.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if `window` is zero.
+  pub fn with_window(window: usize) -> Self {
+    assert!(window > 0, "frame stats window must be greater than zero");
+
+    Self {
+      window,
+      frame_times: VecDeque::with_capacity(window),
+      phase_times: BTreeMap::new(),
+      summary_interval: Some(seconds(10.0)),
+      since_summary: Duration::ZERO,
+    }
+  }
+
+  /// Returns the number of frames the statistics are computed over.
+  pub fn len(&self) -> usize {
+    self.frame_times.len()
+  }
+
+  /// Returns `true` if no frames have been recorded.
+  pub fn is_empty(&self) -> bool {
+    self.frame_times.is_empty()
+  }
+
+  /// Records the duration of a frame, discarding the oldest frame if the
+  /// window is full.
+  pub fn record_frame(&mut self, duration: Duration) {
+    push_bounded(&mut self.frame_times, self.window, duration);
+
+    self.since_summary += duration;
+  }
+
+  /// Records the time spent in a named phase of the current frame.
+  pub fn record_phase(&mut self, name: &str, duration: Duration) {
+    let window = self.window;
+
+    if !self.phase_times.contains_key(name) {
+      self.phase_times.insert(name.into(), VecDeque::with_capacity(window));
+    }
+
+    push_bounded(self.phase_times.get_mut(name).unwrap(), window, duration);
+  }
+
+  /// Returns the duration of the most recent frame.
+  pub fn last(&self) -> Option<Duration> {
+    self.frame_times.back().cloned()
+  }
+
+  /// Returns the average frame time.
+  pub fn average(&self) -> Option<Duration> {
+    average(self.frame_times.iter().cloned())
+  }
+
+  /// Returns the shortest frame time.
+  pub fn min(&self) -> Option<Duration> {
+    self.frame_times.iter().cloned().fold(None, |min, time| match min {
+      Some(min) if min <= time => Some(min),
+      _ => Some(time),
+    })
+  }
+
+  /// Returns the longest frame time.
+  pub fn max(&self) -> Option<Duration> {
+    self.frame_times.iter().cloned().fold(None, |max, time| match max {
+      Some(max) if max >= time => Some(max),
+      _ => Some(time),
+    })
+  }
+
+  /// Returns the average frame time of the slowest 1% of frames, the "1% low".
+  pub fn low_1_percent(&self) -> Option<Duration> {
+    self.slowest_average(0.01)
+  }
+
+  /// Returns the average frame time of the slowest 0.1% of frames, the "0.1%
+  /// low".
+  pub fn low_0_1_percent(&self) -> Option<Duration> {
+    self.slowest_average(0.001)
+  }
+
+  /// Returns a histogram of frame times with `bucket_count` buckets of
+  /// `bucket_width` each.
+  ///
+  /// Each element is the number of frames in that bucket. The last bucket also
+  /// counts all frames longer than the histogram.
+  pub fn histogram(&self, bucket_width: Duration, bucket_count: usize) -> Vec<usize> {
+    let mut buckets = vec![0; bucket_count];
+
+    if bucket_count == 0 || bucket_width == Duration::ZERO {
+      return buckets;
+    }
+
+    for time in &self.frame_times {
+      let index = (time.as_secs() / bucket_width.as_secs()) as usize;
+
+      buckets[index.min(bucket_count - 1)] += 1;
+    }
+
+    buckets
+  }
+
+  /// Returns the names of all recorded phases in alphabetical order.
+  pub fn phases(&self) -> impl Iterator<Item = &str> {
+    self.phase_times.keys().map(String::as_str)
+  }
+
+  /// Returns the average time spent in the named phase per frame.
+  pub fn phase_average(&self, name: &str) -> Option<Duration> {
+    average(self.phase_times.get(name)?.iter().cloned())
+  }
+
+  /// Returns the longest time spent in the named phase in a single frame.
+  pub fn phase_max(&self, name: &str) -> Option<Duration> {
+    self.phase_times.get(name)?.iter().cloned().fold(None, |max, time| match max {
+      Some(max) if max >= time => Some(max),
+      _ => Some(time),
+    })
+  }
+
+  /// Sets how often `log_summary_if_due()` writes a summary, or `None` to never
+  /// write one.
+  pub fn set_summary_interval(&mut self, value: Option<Duration>) {
+    self.summary_interval = value;
+  }
+
+  /// Writes a summary of the statistics to the given logger if at least one
+  /// summary interval of frame time has been recorded since the last summary.
+  pub fn log_summary_if_due(&mut self, logger: &log::Logger) {
+    match self.summary_interval {
+      Some(interval) if self.since_summary >= interval => {}
+      _ => return,
+    }
+
+    self.since_summary = Duration::ZERO;
+    self.log_summary(logger);
+  }
+
+  /// Writes a summary of the statistics to the given logger.
+  pub fn log_summary(&self, logger: &log::Logger) {
+    let average = match self.average() {
+      Some(average) => average,
+      None => return,
+    };
+
+    log::info!(logger, "frame stats";
+      "frames" => self.len(),
+      "average" => average,
+      "min" => log::Debug(self.min()),
+      "max" => log::Debug(self.max()),
+      "low_1%" => log::Debug(self.low_1_percent()),
+      "low_0.1%" => log::Debug(self.low_0_1_percent()),
+    );
+
+    for phase in self.phases() {
+      log::debug!(logger, "phase stats";
+        "phase" => phase,
+        "average" => log::Debug(self.phase_average(phase)),
+        "max" => log::Debug(self.phase_max(phase)),
+      );
+    }
+  }
+
+  /// Returns the average of the slowest `fraction` of frames, which is always
+  /// at least one frame.
+  fn slowest_average(&self, fraction: f64) -> Option<Duration> {
+    let mut times: Vec<Duration> = self.frame_times.iter().cloned().collect();
+
+    times.sort_by(|a, b| b.partial_cmp(a).unwrap_or(cmp::Ordering::Equal));
+
+    let count = ((times.len() as f64 * fraction).ceil() as usize).max(1);
+
+    average(times.into_iter().take(count))
+  }
+}
+
+impl Default for FrameStats {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Pushes a value to the back of a queue, removing values from the front until
+/// it contains at most `max_len` values.
+fn push_bounded(queue: &mut VecDeque<Duration>, max_len: usize, value: Duration) {
+  while queue.len() >= max_len {
+    queue.pop_front();
+  }
+
+  queue.push_back(value);
+}
+
+/// Returns the average of the given durations or `None` if there are none.
+fn average(durations: impl Iterator<Item = Duration>) -> Option<Duration> {
+  let (total, count) =
+    durations.fold((Duration::ZERO, 0), |(total, count), time| (total + time, count + 1));
+
+  if count == 0 {
+    return None;
+  }
+
+  Some(total * (1.0 / f64::from(count)))
+}
@@ -0,0 +1,237 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use std::collections::BTreeMap;
+
+/// Tracks game time, which advances along with real time but can be scaled or
+/// paused.
+#[derive(Debug, Clone)]
+pub struct GameClock {
+  scale: f64,
+  paused: bool,
+  elapsed: Duration,
+  delta: Duration,
+  fixed_interval: Duration,
+}
+
+impl GameClock {
+  /// Creates a new, unpaused game clock with a time scale of `1.0`.
+  pub fn new() -> Self {
+    Self {
+      scale: 1.0,
+      paused: false,
+      elapsed: Duration::ZERO,
+      delta: Duration::ZERO,
+      fixed_interval: Duration::ZERO,
+    }
+  }
+
+  /// Returns the time scale of the clock.
+  pub fn scale(&self) -> f64 {
+    self.scale
+  }
+
+  /// Sets the time scale of the clock.
+  ///
+  /// Game time advances by `scale` seconds for every second of real time, so
+  /// a scale of `0.5` is half speed and a scale of `2.0` is double speed.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if `scale` is negative.
+  pub fn set_scale(&mut self, scale: f64) {
+    assert!(scale >= 0.0, "game clock scale cannot be negative");
+
+    self.scale = scale;
+  }
+
+  /// Returns `true` if the clock is paused.
+  pub fn is_paused(&self) -> bool {
+    self.paused
+  }
+
+  /// Sets whether the clock is paused.
+  ///
+  /// A paused clock does not advance, and its delta is zero.
+  pub fn set_paused(&mut self, paused: bool) {
+    self.paused = paused;
+  }
+
+  /// Pauses the clock.
+  pub fn pause(&mut self) {
+    self.paused = true;
+  }
+
+  /// Resumes the clock if it is paused.
+  pub fn resume(&mut self) {
+    self.paused = false;
+  }
+
+  /// Returns the total game time elapsed.
+  pub fn elapsed(&self) -> Duration {
+    self.elapsed
+  }
+
+  /// Returns the game time elapsed during the most recent advance.
+  pub fn delta(&self) -> Duration {
+    self.delta
+  }
+
+  /// Returns the game time that elapses during one fixed update, which is the
+  /// fixed interval of real time scaled by the time scale of the clock.
+  ///
+  /// Systems that run in `Phase::FixedUpdate` should use this instead of
+  /// `delta()`, which is the game time elapsed during the whole frame. It is
+  /// zero if the clock is paused or no fixed interval is set.
+  pub fn fixed_delta(&self) -> Duration {
+    if self.paused {
+      Duration::ZERO
+    } else {
+      self.fixed_interval * self.scale
+    }
+  }
+
+  /// Sets the duration of real time between fixed updates.
+  ///
+  /// This is set automatically by `GameClocks::advance()`.
+  pub fn set_fixed_interval(&mut self, real_interval: Duration) {
+    self.fixed_interval = real_interval;
+  }
+
+  /// Advances the clock by the given duration of real time, scaled by the time
+  /// scale of the clock.
+  pub fn advance(&mut self, real_delta: Duration) {
+    self.delta = if self.paused { Duration::ZERO } else { real_delta * self.scale };
+    self.elapsed += self.delta;
+  }
+}
+
+impl Default for GameClock {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// A resource containing named game clocks which are all driven by the same
+/// real time `Clock`.
+///
+/// Each game clock has its own time scale and pause state. For example, the
+/// `WORLD` clock can be paused while a menu is open without pausing the `UI`
+/// clock that animates the menu.
+#[derive(Debug)]
+pub struct GameClocks {
+  real_elapsed: Duration,
+  real_delta: Duration,
+  interval_progress: f64,
+  clocks: BTreeMap<String, GameClock>,
+}
+
+impl GameClocks {
+  /// The name of the game clock for world simulation.
+  pub const WORLD: &'static str = "world";
+  /// The name of the game clock for user interfaces.
+  pub const UI: &'static str = "ui";
+  /// The name of the game clock for cutscenes.
+  pub const CUTSCENE: &'static str = "cutscene";
+
+  /// Creates a new set of game clocks containing the `WORLD`, `UI`, and
+  /// `CUTSCENE` clocks.
+  pub fn new() -> Self {
+    let mut clocks = Self {
+      real_elapsed: Duration::ZERO,
+      real_delta: Duration::ZERO,
+      interval_progress: 0.0,
+      clocks: BTreeMap::new(),
+    };
+
+    clocks.insert(Self::WORLD);
+    clocks.insert(Self::UI);
+    clocks.insert(Self::CUTSCENE);
+    clocks
+  }
+
+  /// Returns the total real time elapsed.
+  pub fn real_elapsed(&self) -> Duration {
+    self.real_elapsed
+  }
+
+  /// Returns the real time elapsed during the most recent advance.
+  pub fn real_delta(&self) -> Duration {
+    self.real_delta
+  }
+
+  /// Returns the fraction of a fixed interval of real time that has elapsed
+  /// since the last fixed update, from `0.0` up to but not including `1.0`.
+  ///
+  /// Systems in the `Render` phase can use this to interpolate between the two
+  /// most recent states of a simulation that runs in `FixedUpdate`.
+  pub fn interval_progress(&self) -> f64 {
+    self.interval_progress
+  }
+
+  /// Returns a reference to the `WORLD` game clock.
+  pub fn world(&self) -> &GameClock {
+    self.expect(Self::WORLD)
+  }
+
+  /// Returns a reference to the `UI` game clock.
+  pub fn ui(&self) -> &GameClock {
+    self.expect(Self::UI)
+  }
+
+  /// Returns a reference to the `CUTSCENE` game clock.
+  pub fn cutscene(&self) -> &GameClock {
+    self.expect(Self::CUTSCENE)
+  }
+
+  /// Returns a reference to the game clock with the given name if it exists.
+  pub fn get(&self, name: &str) -> Option<&GameClock> {
+    self.clocks.get(name)
+  }
+
+  /// Returns a mutable reference to the game clock with the given name if it
+  /// exists.
+  pub fn get_mut(&mut self, name: &str) -> Option<&mut GameClock> {
+    self.clocks.get_mut(name)
+  }
+
+  /// Returns a mutable reference to the game clock with the given name,
+  /// creating a new game clock if it does not exist.
+  pub fn insert(&mut self, name: &str) -> &mut GameClock {
+    self.clocks.entry(name.into()).or_default()
+  }
+
+  /// Advances all game clocks by the real time elapsed between the two most
+  /// recent ticks of the given clock, and records the clock's interval
+  /// progress.
+  ///
+  /// The interval of the given clock is used as the fixed interval of every
+  /// game clock.
+  pub fn advance(&mut self, real_clock: &Clock) {
+    self.real_delta = real_clock.elapsed_delta();
+    self.real_elapsed += self.real_delta;
+    self.interval_progress = real_clock.interval_progress();
+
+    for clock in self.clocks.values_mut() {
+      clock.set_fixed_interval(real_clock.interval());
+      clock.advance(self.real_delta);
+    }
+  }
+
+  /// Returns a reference to the game clock with the given name.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if the game clock does not exist.
+  fn expect(&self, name: &str) -> &GameClock {
+    self.clocks.get(name).unwrap_or_else(|| panic!("no game clock named `{}`", name))
+  }
+}
+
+impl Default for GameClocks {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -13,9 +13,9 @@ pub struct Instant(std::time::Instant);
 
 impl Instant {
   /// Returns a value representing the duration of time elapsed since this
-  /// instant in time.
+  /// instant in time according to the global time source.
   pub fn elapsed(&self) -> Duration {
-    self.0.elapsed().into()
+    now() - *self
   }
 }
 
@@ -32,6 +32,15 @@ impl From<Instant> for std::time::Instant {
   }
 }
 
+// Implement addition to offset instants by durations.
+impl ops::Add<Duration> for Instant {
+  type Output = Self;
+
+  fn add(self, other: Duration) -> Self::Output {
+    Self(self.0 + std::time::Duration::try_from(other).expect("could not add duration to instant"))
+  }
+}
+
 // Implement subtraction to create durations.
 impl ops::Sub<Instant> for Instant {
   type Output = Duration;
@@ -48,7 +57,8 @@ impl fmt::Debug for Instant {
   }
 }
 
-/// Returns a value representing the current instant in time.
+/// Returns a value representing the current instant in time according to the
+/// global time source.
 pub fn now() -> Instant {
-  std::time::Instant::now().into()
+  source().now()
 }
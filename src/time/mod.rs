@@ -4,16 +4,31 @@
 
 mod clock;
 mod duration;
+mod easing;
+mod executor;
+mod frame_stats;
+mod game_clock;
 mod instant;
+mod profile;
+mod source;
+mod timers;
+mod tween;
 
-pub use self::{clock::*, duration::*, instant::*};
+pub use self::{
+  clock::*, duration::*, easing::*, executor::*, frame_stats::*, game_clock::*, instant::*,
+  profile::*, source::*, timers::*, tween::*,
+};
 
 use super::*;
 
+/// Blocks the current thread for at least the given duration using the global
+/// time source.
 pub fn sleep(duration: Duration) {
-  std::thread::sleep(duration.try_into().expect("could not sleep for given duration"));
+  source().sleep(duration);
 }
 
+/// Blocks the current thread for the given duration using the global time
+/// source, spinning for better accuracy than `sleep()`.
 pub fn spin_sleep(duration: Duration) {
-  spin_sleep::sleep(duration.try_into().expect("could not spin-sleep for given duration"));
+  source().spin_sleep(duration);
 }
@@ -0,0 +1,229 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{self, Write as _};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Profiles the rest of the enclosing scope as a span with the given name.
+///
+/// Spans are only recorded while profiling is enabled with
+/// `time::set_profiling_enabled`. Recorded spans can be exported with
+/// `time::write_chrome_trace`.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn render_frame() {
+///   nova::profile_scope!("render_frame");
+///
+///   // …
+/// }
+/// ```
+#[macro_export]
+macro_rules! profile_scope {
+  ($name:expr) => {
+    let _profile_scope = $crate::time::ProfileScope::new($name);
+  };
+}
+
+/// The maximum number of recorded spans kept at once.
+///
+/// When more spans are recorded, the oldest are discarded.
+pub const MAX_PROFILE_SPANS: usize = 1 << 16;
+
+lazy_static! {
+  /// The instant that span times are measured from.
+  ///
+  /// Profiling always uses the system clock so that spans measure real time
+  /// even when the time source is overridden.
+  static ref EPOCH: std::time::Instant = std::time::Instant::now();
+  static ref PROFILER: Mutex<Profiler> = Mutex::new(Profiler::default());
+}
+
+/// Whether spans are currently being recorded.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The next thread id to assign.
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+  /// The profiling id of the current thread.
+  static THREAD_ID: u64 = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+}
+
+/// A span of time recorded by a `ProfileScope`.
+#[derive(Debug, Clone)]
+pub struct ProfileSpan {
+  /// Name of the span.
+  pub name: &'static str,
+  /// Profiling id of the thread the span was recorded on.
+  pub thread_id: u64,
+  /// Time the span started, relative to when profiling was first used.
+  pub start: Duration,
+  /// Time the span ended, relative to when profiling was first used.
+  pub end: Duration,
+}
+
+/// A guard that records a `ProfileSpan` from when it is created until it is
+/// dropped.
+///
+/// This is usually created with the `profile_scope!` macro.
+#[derive(Debug)]
+pub struct ProfileScope {
+  name: &'static str,
+  start: Option<std::time::Instant>,
+}
+
+#[derive(Default)]
+struct Profiler {
+  spans: VecDeque<ProfileSpan>,
+  thread_names: BTreeMap<u64, String>,
+}
+
+impl ProfileScope {
+  /// Starts a new span with the given name if profiling is enabled.
+  pub fn new(name: &'static str) -> Self {
+    let start = if is_profiling_enabled() { Some(std::time::Instant::now()) } else { None };
+
+    ProfileScope { name, start }
+  }
+}
+
+impl Drop for ProfileScope {
+  fn drop(&mut self) {
+    let start = match self.start {
+      Some(start) => start,
+      None => return,
+    };
+
+    let end = std::time::Instant::now();
+    let thread_id = current_thread_id();
+
+    let mut profiler = PROFILER.lock();
+
+    // Name the thread the first time it records a span.
+    if !profiler.thread_names.contains_key(&thread_id) {
+      let name = std::thread::current()
+        .name()
+        .map(String::from)
+        .unwrap_or_else(|| format!("thread {}", thread_id));
+
+      profiler.thread_names.insert(thread_id, name);
+    }
+
+    if profiler.spans.len() == MAX_PROFILE_SPANS {
+      profiler.spans.pop_front();
+    }
+
+    profiler.spans.push_back(ProfileSpan {
+      name: self.name,
+      thread_id,
+      start: (start - *EPOCH).into(),
+      end: (end - *EPOCH).into(),
+    });
+  }
+}
+
+/// Returns `true` if profiling spans are currently being recorded.
+pub fn is_profiling_enabled() -> bool {
+  ENABLED.load(Ordering::Relaxed)
+}
+
+/// Enables or disables recording of profiling spans.
+pub fn set_profiling_enabled(enabled: bool) {
+  // Make sure the epoch is set before any span can start.
+  lazy_static::initialize(&EPOCH);
+
+  ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Sets the name of the current thread in exported profiling traces.
+pub fn set_profile_thread_name(name: impl Into<String>) {
+  PROFILER.lock().thread_names.insert(current_thread_id(), name.into());
+}
+
+/// Removes and returns all recorded profiling spans, oldest first.
+///
+/// At most `MAX_PROFILE_SPANS` of the most recent spans are kept.
+pub fn take_profile_spans() -> Vec<ProfileSpan> {
+  PROFILER.lock().spans.drain(..).collect()
+}
+
+/// Writes all recorded profiling spans to a file in the Chrome trace event
+/// format, which can be opened in `about:tracing`, then clears them.
+pub fn write_chrome_trace(vfs: &vfs::Context, path: impl AsRef<vfs::Path>) -> io::Result<()> {
+  let (spans, thread_names) = {
+    let mut profiler = PROFILER.lock();
+
+    (profiler.spans.drain(..).collect::<Vec<_>>(), profiler.thread_names.clone())
+  };
+
+  let mut events = Vec::with_capacity(spans.len() + thread_names.len());
+
+  for (thread_id, name) in thread_names {
+    events.push(serde_json::json!({
+      "name": "thread_name",
+      "ph": "M",
+      "pid": 1,
+      "tid": thread_id,
+      "args": { "name": name },
+    }));
+  }
+
+  for span in spans {
+    events.push(serde_json::json!({
+      "name": span.name,
+      "cat": "nova",
+      "ph": "X",
+      "pid": 1,
+      "tid": span.thread_id,
+      "ts": span.start.as_secs() * 1_000_000.0,
+      "dur": (span.end - span.start).as_secs() * 1_000_000.0,
+    }));
+  }
+
+  let trace = serde_json::json!({ "traceEvents": events, "displayTimeUnit": "ms" });
+
+  let mut file = vfs.create(path)?;
+
+  serde_json::to_writer(&mut file, &trace)?;
+  file.flush()
+}
+
+/// Returns the profiling id of the current thread.
+fn current_thread_id() -> u64 {
+  THREAD_ID.with(|id| *id)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn keeps_the_most_recent_spans_in_real_time() {
+    // Spans should not be affected by a source where time never passes.
+    let _source = override_source(Arc::new(ManualSource::new()));
+
+    set_profiling_enabled(true);
+    take_profile_spans();
+
+    for _ in 0..MAX_PROFILE_SPANS {
+      let _scope = ProfileScope::new("old");
+    }
+
+    for _ in 0..10 {
+      let _scope = ProfileScope::new("new");
+    }
+
+    set_profiling_enabled(false);
+
+    let spans = take_profile_spans();
+
+    assert_eq!(spans.len(), MAX_PROFILE_SPANS);
+    assert_eq!(spans.iter().filter(|span| span.name == "new").count(), 10);
+    assert!(spans[spans.len() - 1].end.as_secs() > spans[0].start.as_secs());
+  }
+}
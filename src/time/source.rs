@@ -0,0 +1,229 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+
+lazy_static! {
+  static ref SOURCE: RwLock<Arc<dyn Source>> = RwLock::new(Arc::new(SystemSource));
+}
+
+thread_local! {
+  /// The time source overriding the global source on the current thread.
+  static THREAD_SOURCE: RefCell<Option<Arc<dyn Source>>> = RefCell::new(None);
+}
+
+/// A source of the current time which can also put the current thread to
+/// sleep.
+///
+/// By default, `now()`, `sleep()`, `spin_sleep()`, and `Clock` use the
+/// `SystemSource`. Tests can replace it on the current thread with a
+/// `ManualSource` using `override_source()` to control the passage of time.
+pub trait Source: fmt::Debug + Send + Sync {
+  /// Returns the current instant in time.
+  fn now(&self) -> Instant;
+
+  /// Blocks the current thread for at least the given duration.
+  fn sleep(&self, duration: Duration);
+
+  /// Blocks the current thread for the given duration, using a spin loop for
+  /// better accuracy than `sleep()` at the cost of CPU time.
+  fn spin_sleep(&self, duration: Duration);
+}
+
+/// A time `Source` based on the system's monotonic clock.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemSource;
+
+impl Source for SystemSource {
+  fn now(&self) -> Instant {
+    std::time::Instant::now().into()
+  }
+
+  fn sleep(&self, duration: Duration) {
+    std::thread::sleep(duration.try_into().expect("could not sleep for given duration"));
+  }
+
+  fn spin_sleep(&self, duration: Duration) {
+    spin_sleep::sleep(duration.try_into().expect("could not spin-sleep for given duration"));
+  }
+}
+
+/// A time `Source` that only advances when told to, for deterministic tests.
+///
+/// Sleeping never blocks. By default, sleeping also does not advance time, but
+/// this can be changed with `set_sleep_advances()` so that code waiting for
+/// time to pass, such as a synchronized `Clock`, behaves as if it slept.
+#[derive(Debug)]
+pub struct ManualSource {
+  origin: Instant,
+  state: Mutex<ManualSourceState>,
+}
+
+#[derive(Debug)]
+struct ManualSourceState {
+  elapsed: Duration,
+  sleep_advances: bool,
+}
+
+impl ManualSource {
+  /// Creates a new manual time source starting at the current system time.
+  pub fn new() -> Self {
+    Self {
+      origin: SystemSource.now(),
+      state: Mutex::new(ManualSourceState { elapsed: Duration::ZERO, sleep_advances: false }),
+    }
+  }
+
+  /// Returns the total duration this source has advanced since it was created.
+  pub fn elapsed(&self) -> Duration {
+    self.state.lock().elapsed
+  }
+
+  /// Advances the current time of this source by the given duration.
+  pub fn advance(&self, duration: Duration) {
+    self.state.lock().elapsed += duration;
+  }
+
+  /// Sets whether sleeping advances the current time of this source by the
+  /// duration of the sleep.
+  pub fn set_sleep_advances(&self, value: bool) {
+    self.state.lock().sleep_advances = value;
+  }
+
+  /// Advances the current time if sleeping is set to advance time.
+  fn advance_for_sleep(&self, duration: Duration) {
+    let mut state = self.state.lock();
+
+    if state.sleep_advances {
+      state.elapsed += duration;
+    }
+  }
+}
+
+impl Default for ManualSource {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Source for ManualSource {
+  fn now(&self) -> Instant {
+    self.origin + self.state.lock().elapsed
+  }
+
+  fn sleep(&self, duration: Duration) {
+    self.advance_for_sleep(duration);
+  }
+
+  fn spin_sleep(&self, duration: Duration) {
+    self.advance_for_sleep(duration);
+  }
+}
+
+/// A guard that overrides the time source of the current thread until it is
+/// dropped.
+///
+/// This is created with `override_source()`.
+#[derive(Debug)]
+pub struct SourceOverride {
+  previous: Option<Arc<dyn Source>>,
+  // Thread-local state must be restored on the same thread.
+  _not_send: std::marker::PhantomData<*const ()>,
+}
+
+impl Drop for SourceOverride {
+  fn drop(&mut self) {
+    let previous = self.previous.take();
+
+    THREAD_SOURCE.with(|source| *source.borrow_mut() = previous);
+  }
+}
+
+/// Returns the time source of the current thread, which is the global time
+/// source unless it has been overridden with `override_source()`.
+pub fn source() -> Arc<dyn Source> {
+  THREAD_SOURCE.with(|source| source.borrow().clone()).unwrap_or_else(|| SOURCE.read().clone())
+}
+
+/// Sets the global time source used by `now()`, `sleep()`, `spin_sleep()`, and
+/// any `Clock` without its own source on threads that have not overridden it.
+pub fn set_source(source: Arc<dyn Source>) {
+  *SOURCE.write() = source;
+}
+
+/// Overrides the time source on the current thread only until the returned
+/// guard is dropped.
+///
+/// Unlike `set_source()`, this does not affect other threads, so tests that
+/// run in parallel can each control their own passage of time.
+pub fn override_source(source: Arc<dyn Source>) -> SourceOverride {
+  let previous = THREAD_SOURCE.with(|current| current.borrow_mut().replace(source));
+
+  SourceOverride { previous, _not_send: std::marker::PhantomData }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn override_only_affects_current_thread() {
+    let manual = Arc::new(ManualSource::new());
+    let _guard = override_source(manual.clone());
+
+    manual.advance(seconds(10.0));
+
+    assert!(now() == manual.now());
+
+    let other = std::thread::spawn(|| now()).join().expect("thread panicked");
+
+    assert!(other != manual.now());
+  }
+
+  #[test]
+  fn override_is_restored_when_dropped() {
+    let outer = Arc::new(ManualSource::new());
+    let inner = Arc::new(ManualSource::new());
+    let _outer_guard = override_source(outer.clone());
+
+    inner.advance(seconds(1.0));
+
+    {
+      let _inner_guard = override_source(inner.clone());
+
+      assert!(now() == inner.now());
+    }
+
+    assert!(now() == outer.now());
+  }
+
+  #[test]
+  fn synchronized_clock_sleeps_through_manual_source() {
+    let manual = Arc::new(ManualSource::new());
+    let _guard = override_source(manual.clone());
+    let mut clock = Clock::new();
+
+    manual.set_sleep_advances(true);
+    clock.set_interval(seconds(0.25));
+    clock.set_intervals_synchronized(true);
+    clock.tick();
+
+    for ticks in 1..=4 {
+      clock.tick();
+
+      // Each tick sleeps until exactly one more interval has elapsed.
+      assert_eq!(clock.elapsed_delta(), seconds(0.25));
+      assert_eq!(clock.elapsed_intervals_delta(), 1);
+      assert_eq!(manual.elapsed(), seconds(0.25) * ticks as f64);
+    }
+
+    // Time that already passed is not slept again.
+    manual.advance(seconds(0.375));
+    clock.tick();
+
+    assert_eq!(clock.elapsed_delta(), seconds(0.375));
+    assert_eq!(clock.elapsed_intervals_delta(), 1);
+    assert_eq!(clock.interval_progress(), 0.5);
+  }
+}
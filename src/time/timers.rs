@@ -0,0 +1,295 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use crate::ecs::storage::DenseVecStorage;
+use crate::ecs::Join as _;
+
+/// Component that finishes after a duration of game time, either once or
+/// repeatedly.
+#[derive(Debug, Clone)]
+pub struct Timer {
+  duration: Duration,
+  elapsed: Duration,
+  repeating: bool,
+  finished: bool,
+}
+
+impl Timer {
+  /// Creates a new timer that finishes once after the given duration.
+  pub fn once(duration: Duration) -> Self {
+    Self { duration, elapsed: Duration::ZERO, repeating: false, finished: false }
+  }
+
+  /// Creates a new timer that finishes every time the given duration elapses.
+  pub fn repeating(duration: Duration) -> Self {
+    Self { repeating: true, ..Self::once(duration) }
+  }
+
+  /// Returns the duration of the timer.
+  pub fn duration(&self) -> Duration {
+    self.duration
+  }
+
+  /// Returns the time elapsed since the timer started or, if it is repeating,
+  /// since it last finished.
+  pub fn elapsed(&self) -> Duration {
+    self.elapsed
+  }
+
+  /// Returns the time remaining until the timer next finishes.
+  pub fn remaining(&self) -> Duration {
+    self.duration - self.elapsed
+  }
+
+  /// Returns `true` if the timer repeats.
+  pub fn is_repeating(&self) -> bool {
+    self.repeating
+  }
+
+  /// Returns `true` if the timer is a one-shot timer that has finished.
+  pub fn is_finished(&self) -> bool {
+    self.finished
+  }
+
+  /// Restarts the timer from zero.
+  pub fn reset(&mut self) {
+    self.elapsed = Duration::ZERO;
+    self.finished = false;
+  }
+
+  /// Advances the timer by the given duration and returns the number of times
+  /// it finished.
+  ///
+  /// A one-shot timer finishes at most once and then stops advancing until it
+  /// is reset. A repeating timer can finish more than once if `delta` is longer
+  /// than its duration.
+  pub fn tick(&mut self, delta: Duration) -> u32 {
+    if self.finished {
+      return 0;
+    }
+
+    self.elapsed += delta;
+
+    if self.elapsed < self.duration {
+      return 0;
+    }
+
+    if !self.repeating || self.duration == Duration::ZERO {
+      self.elapsed = self.duration;
+      self.finished = !self.repeating;
+
+      return 1;
+    }
+
+    let count = (self.elapsed.as_secs() / self.duration.as_secs()).floor();
+
+    self.elapsed = self.elapsed - self.duration * count;
+
+    count as u32
+  }
+}
+
+impl ecs::Component for Timer {
+  type Storage = DenseVecStorage<Self>;
+}
+
+/// Component that limits how often an action can happen.
+///
+/// A cooldown starts ready. Triggering it makes it unready until its duration
+/// of game time has elapsed.
+#[derive(Debug, Clone)]
+pub struct Cooldown {
+  duration: Duration,
+  remaining: Duration,
+}
+
+impl Cooldown {
+  /// Creates a new, ready cooldown with the given duration.
+  pub fn new(duration: Duration) -> Self {
+    Self { duration, remaining: Duration::ZERO }
+  }
+
+  /// Returns the duration of the cooldown.
+  pub fn duration(&self) -> Duration {
+    self.duration
+  }
+
+  /// Returns the time remaining until the cooldown is ready.
+  pub fn remaining(&self) -> Duration {
+    self.remaining
+  }
+
+  /// Returns `true` if the cooldown is ready to be triggered.
+  pub fn is_ready(&self) -> bool {
+    self.remaining == Duration::ZERO
+  }
+
+  /// Triggers the cooldown if it is ready, returning `true` if it was
+  /// triggered.
+  pub fn trigger(&mut self) -> bool {
+    if !self.is_ready() {
+      return false;
+    }
+
+    self.remaining = self.duration;
+
+    true
+  }
+
+  /// Makes the cooldown ready immediately.
+  pub fn reset(&mut self) {
+    self.remaining = Duration::ZERO;
+  }
+
+  /// Advances the cooldown by the given duration, returning `true` if it
+  /// became ready.
+  pub fn tick(&mut self, delta: Duration) -> bool {
+    if self.is_ready() {
+      return false;
+    }
+
+    self.remaining = self.remaining - delta;
+    self.is_ready()
+  }
+}
+
+impl ecs::Component for Cooldown {
+  type Storage = DenseVecStorage<Self>;
+}
+
+/// Component that measures elapsed game time.
+#[derive(Debug, Clone, Default)]
+pub struct Stopwatch {
+  elapsed: Duration,
+  paused: bool,
+}
+
+impl Stopwatch {
+  /// Creates a new, running stopwatch.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Returns the time measured by the stopwatch.
+  pub fn elapsed(&self) -> Duration {
+    self.elapsed
+  }
+
+  /// Returns `true` if the stopwatch is paused.
+  pub fn is_paused(&self) -> bool {
+    self.paused
+  }
+
+  /// Pauses the stopwatch.
+  pub fn pause(&mut self) {
+    self.paused = true;
+  }
+
+  /// Resumes the stopwatch if it is paused.
+  pub fn resume(&mut self) {
+    self.paused = false;
+  }
+
+  /// Resets the measured time to zero without changing whether the stopwatch
+  /// is paused.
+  pub fn reset(&mut self) {
+    self.elapsed = Duration::ZERO;
+  }
+
+  /// Advances the stopwatch by the given duration if it is not paused.
+  pub fn tick(&mut self, delta: Duration) {
+    if !self.paused {
+      self.elapsed += delta;
+    }
+  }
+}
+
+impl ecs::Component for Stopwatch {
+  type Storage = DenseVecStorage<Self>;
+}
+
+/// An event emitted by `UpdateTimers`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerEvent {
+  /// The `Timer` of the entity finished.
+  TimerFinished(ecs::Entity),
+  /// The `Cooldown` of the entity became ready.
+  CooldownReady(ecs::Entity),
+}
+
+/// System that advances all timers, cooldowns, and stopwatches by the delta
+/// of a game clock and writes a `TimerEvent` each time one finishes.
+///
+/// This system requires the `GameClocks` and `ecs::EventChannel<TimerEvent>`
+/// resources. By default it must run once per frame in `Phase::Update`; use
+/// `with_fixed_step()` to run it in `Phase::FixedUpdate` instead.
+#[derive(Debug)]
+pub struct UpdateTimers {
+  clock: String,
+  fixed_step: bool,
+}
+
+impl UpdateTimers {
+  /// Creates a new system that uses the `GameClocks::WORLD` clock.
+  pub fn new() -> Self {
+    Self::with_clock(GameClocks::WORLD)
+  }
+
+  /// Creates a new system that uses the game clock with the given name.
+  pub fn with_clock(name: &str) -> Self {
+    Self { clock: name.into(), fixed_step: false }
+  }
+
+  /// Returns the system configured to advance by the fixed delta of its game
+  /// clock so that it can run in `Phase::FixedUpdate`.
+  pub fn with_fixed_step(mut self) -> Self {
+    self.fixed_step = true;
+    self
+  }
+}
+
+impl Default for UpdateTimers {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<'a> ecs::System<'a> for UpdateTimers {
+  type SystemData = (
+    ecs::Entities<'a>,
+    ecs::Resource<'a, GameClocks>,
+    ecs::ResourceMut<'a, ecs::EventChannel<TimerEvent>>,
+    ecs::ComponentsMut<'a, Timer>,
+    ecs::ComponentsMut<'a, Cooldown>,
+    ecs::ComponentsMut<'a, Stopwatch>,
+  );
+
+  fn run(
+    &mut self,
+    (entities, clocks, mut events, mut timers, mut cooldowns, mut stopwatches): Self::SystemData,
+  ) {
+    let delta = match clocks.get(&self.clock) {
+      Some(clock) if self.fixed_step => clock.fixed_delta(),
+      Some(clock) => clock.delta(),
+      None => return,
+    };
+
+    for (entity, timer) in (&entities, &mut timers).join() {
+      for _ in 0..timer.tick(delta) {
+        events.single_write(TimerEvent::TimerFinished(entity));
+      }
+    }
+
+    for (entity, cooldown) in (&entities, &mut cooldowns).join() {
+      if cooldown.tick(delta) {
+        events.single_write(TimerEvent::CooldownReady(entity));
+      }
+    }
+
+    for stopwatch in (&mut stopwatches).join() {
+      stopwatch.tick(delta);
+    }
+  }
+}
@@ -0,0 +1,362 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use crate::ecs::storage::DenseVecStorage;
+use crate::ecs::Join as _;
+use crate::math::Vector2;
+
+/// A value that can be interpolated by a `Tween`.
+pub trait Tweenable: Copy {
+  /// Returns the value interpolated between `from` and `to` by `t`, where `0.0`
+  /// is `from` and `1.0` is `to`.
+  ///
+  /// Values of `t` outside of that range extrapolate.
+  fn lerp(from: Self, to: Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+  fn lerp(from: Self, to: Self, t: f32) -> Self {
+    from + (to - from) * t
+  }
+}
+
+impl Tweenable for Point2<f32> {
+  fn lerp(from: Self, to: Self, t: f32) -> Self {
+    from + (to - from) * t
+  }
+}
+
+impl Tweenable for Vector2<f32> {
+  fn lerp(from: Self, to: Self, t: f32) -> Self {
+    from + (to - from) * t
+  }
+}
+
+impl Tweenable for Size<f32> {
+  fn lerp(from: Self, to: Self, t: f32) -> Self {
+    Size::new(f32::lerp(from.width, to.width, t), f32::lerp(from.height, to.height, t))
+  }
+}
+
+impl Tweenable for Rect<f32> {
+  fn lerp(from: Self, to: Self, t: f32) -> Self {
+    Rect { start: Point2::lerp(from.start, to.start, t), end: Point2::lerp(from.end, to.end, t) }
+  }
+}
+
+impl Tweenable for gfx::Color {
+  fn lerp(from: Self, to: Self, t: f32) -> Self {
+    gfx::Color::new(
+      f32::lerp(from.r, to.r, t),
+      f32::lerp(from.g, to.g, t),
+      f32::lerp(from.b, to.b, t),
+      f32::lerp(from.a, to.a, t),
+    )
+  }
+}
+
+/// How many times a `Tween` plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeat {
+  /// The tween plays once.
+  Once,
+  /// The tween plays the given number of times in total.
+  Times(u32),
+  /// The tween plays forever.
+  Forever,
+}
+
+impl Default for Repeat {
+  fn default() -> Self {
+    Repeat::Once
+  }
+}
+
+/// Component that interpolates a value from one end to another over a duration
+/// of game time.
+#[derive(Debug, Clone)]
+pub struct Tween<T> {
+  from: T,
+  to: T,
+  duration: Duration,
+  delay: Duration,
+  easing: Easing,
+  repeat: Repeat,
+  yoyo: bool,
+  elapsed: Duration,
+}
+
+impl<T: Tweenable> Tween<T> {
+  /// Creates a new tween from one value to another over the given duration,
+  /// with linear easing and no delay or repetition.
+  pub fn new(from: T, to: T, duration: Duration) -> Self {
+    Self {
+      from,
+      to,
+      duration,
+      delay: Duration::ZERO,
+      easing: Easing::Linear,
+      repeat: Repeat::Once,
+      yoyo: false,
+      elapsed: Duration::ZERO,
+    }
+  }
+
+  /// Sets the easing curve of the tween.
+  pub fn with_easing(mut self, easing: Easing) -> Self {
+    self.easing = easing;
+    self
+  }
+
+  /// Sets a delay before the tween starts.
+  pub fn with_delay(mut self, delay: Duration) -> Self {
+    self.delay = delay;
+    self
+  }
+
+  /// Sets how many times the tween plays.
+  pub fn with_repeat(mut self, repeat: Repeat) -> Self {
+    self.repeat = repeat;
+    self
+  }
+
+  /// Sets whether every other play of the tween runs in reverse, from the end
+  /// value back to the start value.
+  pub fn with_yoyo(mut self, yoyo: bool) -> Self {
+    self.yoyo = yoyo;
+    self
+  }
+
+  /// Returns the total duration of the tween including its delay, or `None`
+  /// if it repeats forever.
+  pub fn total_duration(&self) -> Option<Duration> {
+    match self.repeat {
+      Repeat::Once => Some(self.delay + self.duration),
+      Repeat::Times(count) => Some(self.delay + self.duration * f64::from(count)),
+      Repeat::Forever => None,
+    }
+  }
+
+  /// Returns the time elapsed since the tween started, including its delay.
+  pub fn elapsed(&self) -> Duration {
+    self.elapsed
+  }
+
+  /// Returns `true` if the tween has finished playing.
+  pub fn is_finished(&self) -> bool {
+    match self.total_duration() {
+      Some(total) => self.elapsed >= total,
+      None => false,
+    }
+  }
+
+  /// Advances the tween by the given duration, returning any time left over
+  /// after it finished.
+  pub fn advance(&mut self, delta: Duration) -> Duration {
+    self.elapsed += delta;
+
+    match self.total_duration() {
+      Some(total) if self.elapsed > total => {
+        let overflow = self.elapsed - total;
+
+        self.elapsed = total;
+
+        overflow
+      }
+
+      _ => Duration::ZERO,
+    }
+  }
+
+  /// Restarts the tween from the beginning, including its delay.
+  pub fn reset(&mut self) {
+    self.elapsed = Duration::ZERO;
+  }
+
+  /// Returns the current value of the tween.
+  pub fn value(&self) -> T {
+    T::lerp(self.from, self.to, self.easing.apply(self.progress()))
+  }
+
+  /// Returns the linear progress of the current play of the tween, from `0.0`
+  /// at the start value to `1.0` at the end value.
+  fn progress(&self) -> f32 {
+    if self.elapsed <= self.delay {
+      return 0.0;
+    }
+
+    let active = self.elapsed - self.delay;
+
+    if self.duration == Duration::ZERO {
+      return if self.yoyo && self.play_count().map_or(false, |count| count % 2 == 0) {
+        0.0
+      } else {
+        1.0
+      };
+    }
+
+    let plays = active.as_secs() / self.duration.as_secs();
+    let mut play = plays.floor();
+    let mut t = plays - play;
+
+    // At the exact end of a play, finish it rather than start the next one.
+    if t == 0.0 && play > 0.0 {
+      play -= 1.0;
+      t = 1.0;
+    }
+
+    if self.yoyo && play as u64 % 2 == 1 {
+      t = 1.0 - t;
+    }
+
+    t as f32
+  }
+
+  /// Returns the number of times the tween plays, or `None` if it plays
+  /// forever.
+  fn play_count(&self) -> Option<u32> {
+    match self.repeat {
+      Repeat::Once => Some(1),
+      Repeat::Times(count) => Some(count),
+      Repeat::Forever => None,
+    }
+  }
+}
+
+impl<T: Tweenable + Send + Sync + 'static> ecs::Component for Tween<T> {
+  type Storage = DenseVecStorage<Self>;
+}
+
+/// Component that plays a sequence of tweens of the same type one after
+/// another.
+#[derive(Debug, Clone)]
+pub struct TweenSequence<T> {
+  tweens: Vec<Tween<T>>,
+  current: usize,
+}
+
+impl<T: Tweenable> TweenSequence<T> {
+  /// Creates a new sequence of the given tweens.
+  ///
+  /// # Panics
+  ///
+  /// This function panics if there are no tweens or if any tween repeats
+  /// forever, except for the last.
+  pub fn new(tweens: Vec<Tween<T>>) -> Self {
+    assert!(!tweens.is_empty(), "tween sequence must contain at least one tween");
+
+    assert!(
+      tweens[..tweens.len() - 1].iter().all(|tween| tween.total_duration().is_some()),
+      "only the last tween in a sequence can repeat forever"
+    );
+
+    Self { tweens, current: 0 }
+  }
+
+  /// Returns `true` if every tween in the sequence has finished.
+  pub fn is_finished(&self) -> bool {
+    self.current == self.tweens.len() - 1 && self.tweens[self.current].is_finished()
+  }
+
+  /// Advances the sequence by the given duration, moving on to the following
+  /// tweens as each one finishes.
+  pub fn advance(&mut self, delta: Duration) {
+    let mut delta = delta;
+
+    loop {
+      delta = self.tweens[self.current].advance(delta);
+
+      if delta == Duration::ZERO || self.current == self.tweens.len() - 1 {
+        return;
+      }
+
+      self.current += 1;
+    }
+  }
+
+  /// Restarts the sequence from its first tween.
+  pub fn reset(&mut self) {
+    for tween in &mut self.tweens {
+      tween.reset();
+    }
+
+    self.current = 0;
+  }
+
+  /// Returns the current value of the sequence.
+  pub fn value(&self) -> T {
+    self.tweens[self.current].value()
+  }
+}
+
+impl<T: Tweenable + Send + Sync + 'static> ecs::Component for TweenSequence<T> {
+  type Storage = DenseVecStorage<Self>;
+}
+
+/// System that advances all `Tween<T>` and `TweenSequence<T>` components by
+/// the delta of a game clock.
+///
+/// This system requires the `GameClocks` resource. By default it must run once
+/// per frame in `Phase::Update`; use `with_fixed_step()` to run it in
+/// `Phase::FixedUpdate` instead.
+#[derive(Debug)]
+pub struct UpdateTweens<T> {
+  clock: String,
+  fixed_step: bool,
+  _marker: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T> UpdateTweens<T> {
+  /// Creates a new system that uses the `GameClocks::WORLD` clock.
+  pub fn new() -> Self {
+    Self::with_clock(GameClocks::WORLD)
+  }
+
+  /// Creates a new system that uses the game clock with the given name.
+  pub fn with_clock(name: &str) -> Self {
+    Self { clock: name.into(), fixed_step: false, _marker: std::marker::PhantomData }
+  }
+
+  /// Returns the system configured to advance by the fixed delta of its game
+  /// clock so that it can run in `Phase::FixedUpdate`.
+  pub fn with_fixed_step(mut self) -> Self {
+    self.fixed_step = true;
+    self
+  }
+}
+
+impl<T> Default for UpdateTweens<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<'a, T: Tweenable + Send + Sync + 'static> ecs::System<'a> for UpdateTweens<T> {
+  type SystemData = (
+    ecs::Resource<'a, GameClocks>,
+    ecs::ComponentsMut<'a, Tween<T>>,
+    ecs::ComponentsMut<'a, TweenSequence<T>>,
+  );
+
+  fn run(&mut self, (clocks, mut tweens, mut sequences): Self::SystemData) {
+    let delta = match clocks.get(&self.clock) {
+      Some(clock) if self.fixed_step => clock.fixed_delta(),
+      Some(clock) => clock.delta(),
+      None => return,
+    };
+
+    for tween in (&mut tweens).join() {
+      if !tween.is_finished() {
+        tween.advance(delta);
+      }
+    }
+
+    for sequence in (&mut sequences).join() {
+      if !sequence.is_finished() {
+        sequence.advance(delta);
+      }
+    }
+  }
+}
@@ -8,7 +8,7 @@ use crate::screen::{Screen, ScreenRect};
 use nova_core::components::{self, ReadComponents, WriteComponents};
 use nova_core::engine::{Engine, EnginePhase};
 use nova_core::entities::Entity;
-use nova_core::math::{Rect, Size};
+use nova_core::math::{Point2, Rect, Size, Vector2};
 use nova_core::resources::ReadResource;
 use nova_core::systems::derive::*;
 use nova_core::systems::System;
@@ -50,12 +50,7 @@ impl<'a> System<'a> for LayoutElements {
         &input,
         &mut output,
         root,
-        Rect {
-          x1: x,
-          y1: y,
-          x2: x + size.width,
-          y2: y + size.height,
-        },
+        Rect::new(Point2::new(x, y), Point2::new(x + size.width, y + size.height)),
       );
     }
   }
@@ -141,12 +136,7 @@ fn calculate_size(
     .rects
     .insert(
       entity,
-      ScreenRect(Rect {
-        x1: 0.0,
-        y1: 0.0,
-        x2: size.width,
-        y2: size.height,
-      }),
+      ScreenRect(Rect::new(Point2::new(0.0, 0.0), Point2::new(size.width, size.height))),
     )
     .unwrap();
 
@@ -187,21 +177,18 @@ fn offset_children(input: &InputData, output: &mut OutputData, entity: Entity, r
     let child_rect = output.rects.get_mut(child).unwrap();
 
     let x = match h_align {
-      HorizontalAlign::Left => rect.x1,
-      HorizontalAlign::Center => rect.x1 + (rect.width() - child_rect.width()) / 2.0,
-      HorizontalAlign::Right => rect.x2 - child_rect.width(),
+      HorizontalAlign::Left => rect.x1(),
+      HorizontalAlign::Center => rect.x1() + (rect.width() - child_rect.width()) / 2.0,
+      HorizontalAlign::Right => rect.x2() - child_rect.width(),
     };
 
     let y = match v_align {
-      VerticalAlign::Top => rect.y1,
-      VerticalAlign::Center => rect.y1 + (rect.height() - child_rect.height()) / 2.0,
-      VerticalAlign::Bottom => rect.y2 - child_rect.height(),
+      VerticalAlign::Top => rect.y1(),
+      VerticalAlign::Center => rect.y1() + (rect.height() - child_rect.height()) / 2.0,
+      VerticalAlign::Bottom => rect.y2() - child_rect.height(),
     };
 
-    child_rect.x1 += x;
-    child_rect.y1 += y;
-    child_rect.x2 += x;
-    child_rect.y2 += y;
+    child_rect.0 = child_rect.translate(Vector2::new(x, y));
 
     let final_rect = child_rect.0;
 
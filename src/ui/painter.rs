@@ -13,7 +13,7 @@ use crate::text::cache::GlyphCache;
 use crate::text::position::PositionedText;
 use crate::{Color, Screen};
 use nova_core::components;
-use nova_core::math::{Matrix4, Rect, Size};
+use nova_core::math::{Matrix4, Point2, Rect, Size};
 use nova_core::resources::Resources;
 use nova_renderer::images::DeviceImageFormat;
 use nova_renderer::{self as renderer, Render, Renderer, TextureId};
@@ -95,12 +95,7 @@ impl Painter {
       .cache_queued(|rect, bytes| {
         render.textures_mut().copy_to_texture(
           self.glyph_cache_texture,
-          Rect {
-            x1: rect.min.x,
-            y1: rect.min.y,
-            x2: rect.max.x,
-            y2: rect.max.y,
-          },
+          Rect::new(Point2::new(rect.min.x, rect.min.y), Point2::new(rect.max.x, rect.max.y)),
           bytes,
         )
       })
@@ -115,20 +110,10 @@ impl Painter {
 
     canvas.draw_texture(
       true,
-      Rect {
-        x1: 0.0,
-        y1: 0.0,
-        x2: 1024.0,
-        y2: 1024.0,
-      },
+      Rect::new(Point2::new(0.0, 0.0), Point2::new(1024.0, 1024.0)),
       [0.0, 1.0, 0.0, 0.85].into(),
       self.glyph_cache_texture,
-      Rect {
-        x1: 0.0,
-        y1: 0.0,
-        x2: 1.0,
-        y2: 1.0,
-      },
+      Rect::new(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0)),
     );
 
     for entity in nodes.sorted() {
@@ -6,7 +6,7 @@ use crate::text::cache::GlyphCache;
 use crate::text::fonts::FontId;
 use crate::text::position::PositionedGlyph;
 use crate::{Color, Screen};
-use nova_core::math::Rect;
+use nova_core::math::{Point2, Rect};
 use nova_graphics::images::ImageSlice;
 use nova_renderer::{Pipeline, Render, TextureId};
 
@@ -66,20 +66,16 @@ impl<'a, 'b> Canvas<'a, 'b> {
 
       self.draw_texture(
         true,
-        Rect {
-          x1: coords.min.x as f32,
-          y1: coords.min.y as f32,
-          x2: coords.max.x as f32,
-          y2: coords.max.y as f32,
-        },
+        Rect::new(
+          Point2::new(coords.min.x as f32, coords.min.y as f32),
+          Point2::new(coords.max.x as f32, coords.max.y as f32),
+        ),
         *color,
         texture_id,
-        Rect {
-          x1: tex_coords.min.x,
-          y1: tex_coords.min.y,
-          x2: tex_coords.max.x,
-          y2: tex_coords.max.y,
-        },
+        Rect::new(
+          Point2::new(tex_coords.min.x, tex_coords.min.y),
+          Point2::new(tex_coords.max.x, tex_coords.max.y),
+        ),
       );
     }
   }
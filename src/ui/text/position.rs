@@ -49,15 +49,15 @@ impl<'a> System<'a> for PositionText {
         .or_insert_with(PositionedText::default);
 
       let x = match text.h_align {
-        HorizontalAlign::Left => rect.x1,
-        HorizontalAlign::Center => rect.x1 + rect.width() / 2.0,
-        HorizontalAlign::Right => rect.x2,
+        HorizontalAlign::Left => rect.x1(),
+        HorizontalAlign::Center => rect.x1() + rect.width() / 2.0,
+        HorizontalAlign::Right => rect.x2(),
       };
 
       let y = match text.v_align {
-        VerticalAlign::Top => rect.y1,
-        VerticalAlign::Center => rect.y1 + rect.height() / 2.0,
-        VerticalAlign::Bottom => rect.y2,
+        VerticalAlign::Top => rect.y1(),
+        VerticalAlign::Center => rect.y1() + rect.height() / 2.0,
+        VerticalAlign::Bottom => rect.y2(),
       };
 
       let result = GlyphBrushLayout::default_wrap()
@@ -42,6 +42,12 @@ impl Path {
   }
 }
 
+impl AsRef<Path> for Path {
+  fn as_ref(&self) -> &Path {
+    self
+  }
+}
+
 impl AsRef<Path> for str {
   fn as_ref(&self) -> &Path {
     unsafe { &*(self as *const str as *const Path) }
@@ -23,14 +23,17 @@ impl Handle {
   /// Returns the next window event if one is available or `None` if there is no
   /// available event.
   pub fn next_event(&mut self) -> Option<Event> {
-    let event = self.events.try_next().ok()??;
+    // Skip over any winit events that have no corresponding `Event`.
+    loop {
+      let event = self.events.try_next().ok()??;
 
-    Some(match event {
-      winit::WindowEvent::CloseRequested => Event::CloseRequested,
-      winit::WindowEvent::Resized(_) => Event::Resized,
+      return Some(match event {
+        winit::WindowEvent::CloseRequested => Event::CloseRequested,
+        winit::WindowEvent::Resized(_) => Event::Resized,
 
-      _ => return None,
-    })
+        _ => continue,
+      });
+    }
   }
 
   /// Returns a reference to the underlying winit window.
@@ -10,7 +10,7 @@ pub use self::{handle::*, settings::*};
 use super::*;
 
 /// A window event.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Event {
   /// The user requested for the window to close, such as by clicking on the
   /// window's X button.
@@ -29,6 +29,8 @@ pub fn open(thread_scope: &thread::Scope, settings: Settings) -> Result<Handle,
 
   // Start the event loop thread.
   thread_scope.spawn(move |_| {
+    time::set_profile_thread_name("window");
+
     // Set up an event loop and create the window.
     let mut events_loop = winit::EventsLoop::new();
 
@@ -71,6 +73,8 @@ pub fn open(thread_scope: &thread::Scope, settings: Settings) -> Result<Handle,
     // channel is closed on the other end meaning all handles have been dropped
     // and the window should close.
     events_loop.run_forever(|event| {
+      crate::profile_scope!("window_event");
+
       if let winit::Event::WindowEvent { event, .. } = event {
         if send_events.unbounded_send(event).is_err() {
           return winit::ControlFlow::Break;